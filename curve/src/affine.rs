@@ -1,6 +1,7 @@
-use std::ops::{Mul, Add};
+use std::mem;
+use std::ops::{Mul, Add, AddAssign};
 
-use field::{FieldValue, Scalar};
+use field::{FieldValue, Scalar, Choice, ConditionallySelectable, ConstantTimeEq};
 use {Curve, JacobianPoint};
 
 /// Affine point on the curve C
@@ -26,7 +27,7 @@ impl<C: Curve> Point<C> {
 
     /// New point from two field elements
     pub fn new(x: C::Value, y: C::Value) -> Self {
-        Point { x: x, y: y }
+        Point { x, y }
     }
 
     /// Field element of X coordinate
@@ -39,12 +40,42 @@ impl<C: Curve> Point<C> {
         self.y
     }
 
+    /// Test if point satisfies the curve equation (point at infinity included)
+    pub fn is_on_curve(&self) -> bool {
+        self.is_infinity() || self.y.squared() == (self.x.squared() + C::a()) * self.x + C::b()
+    }
+
     /// Deconstruct point into field elements (X, Y)
     pub fn into_parts(self) -> (C::Value, C::Value) {
         (self.x, self.y)
     }
 }
 
+impl<C: Curve> Point<C> where C::Value: ConditionallySelectable + ConstantTimeEq {
+    /// Multiply by scalar without branching on its bits or on the points
+    ///
+    /// Runs `JacobianPoint::mul_ct` and converts back with a single division, the point at
+    /// infinity is selected rather than branched on. Multiplication with `*` is variable-time.
+    pub fn mul_ct<I: Scalar>(self, other: I) -> Self {
+        let (x, y, z) = JacobianPoint::from(self).mul_ct(other).into_parts();
+
+        let infinity = z.ct_is_zero();
+        let z_inv = C::Value::one() / C::Value::conditional_select(&z, &C::Value::one(), infinity);
+        let z_inv2 = z_inv.squared();
+
+        Self::conditional_select(&Point::new(x * z_inv2, y * z_inv2 * z_inv), &Self::infinity(), infinity)
+    }
+}
+
+impl<C: Curve> ConditionallySelectable for Point<C> where C::Value: ConditionallySelectable {
+    fn conditional_select(a: &Self, b: &Self, choice: Choice) -> Self {
+        Point {
+            x: C::Value::conditional_select(&a.x, &b.x, choice),
+            y: C::Value::conditional_select(&a.y, &b.y, choice),
+        }
+    }
+}
+
 impl<I, C: Curve> From<(I, I)> for Point<C>
     where I: Into<C::Value>
 {
@@ -63,14 +94,13 @@ impl<C: Curve> From<JacobianPoint<C>> for Point<C>
     fn from(p: JacobianPoint<C>) -> Self {
         let (x, y, z) = p.into_parts();
 
-        if z == C::Value::zero() {
-            return Self::infinity();
-        }
-
-        let z2 = z.squared();
-        let z3 = z2 * z;
+        let z_inv = match z.invert() {
+            Some(z_inv) => z_inv,
+            None => return Self::infinity(),
+        };
+        let z_inv2 = z_inv.squared();
 
-        (x / z2, y / z3).into()
+        (x * z_inv2, y * z_inv2 * z_inv).into()
     }
 }
 
@@ -87,25 +117,14 @@ impl<C: Curve> Add for Point<C>
         let (x1, y1) = self.into_parts();
         let (x2, y2) = other.into_parts();
 
-        if x1 == x2 {
-            // point doubling
-            if y1 != y2 {
-                return Self::infinity();
-            }
-
-            if y1 == C::Value::zero() {
-                return Self::infinity();
-            }
-
-            let l = (x1.squared() * 3 + C::a()) / (y1 * 2);
-
-            let x = l.squared() - x1 * 2;
-            let y = l * (x1 - x) - y1;
-
-            return (x, y).into();
-        }
+        // the chord slope is undefined for x1 == x2: either P + (-P), or point doubling
+        let l = match (y2 - y1).checked_div(x2 - x1) {
+            Some(l) => l,
+            None if y1 != y2 || y1 == C::Value::zero() => return Self::infinity(),
+            None => (x1.squared() * 3 + C::a()).checked_div(y1 * 2)
+                .expect("tangent slope denominator 2y is zero, curve field of characteristic 2 is not supported"),
+        };
 
-        let l = (y2 - y1) / (x2 - x1);
         let x3 = l.squared() - x1 - x2;
         let y3 = l * (x1 - x3) - y1;
 
@@ -122,18 +141,25 @@ impl<I: Scalar, C: Curve> Mul<I> for Point<C>
         let mut r1 = self;
         for i in 0..I::max_bits() {
             let b = Scalar::bit(&other, i);
-            if b { r0 = r0 + r1.clone() }
-            r1 = r1.clone() + r1;
+            if b { r0 += r1.clone() }
+            r1 += r1.clone();
         }
         r0
     }
 }
 
+impl<C: Curve> AddAssign for Point<C> {
+    fn add_assign(&mut self, other: Self) {
+        let p = mem::replace(self, Self::infinity());
+        *self = p + other;
+    }
+}
+
 #[cfg(test)]
 mod tests {
 
-    use test::{U64Curve, U64MontgomeryCurve, U64KoblitzCurve};
-    use Curve;
+    use test::{U64Curve, U64MontgomeryCurve, U64KoblitzCurve, U64ConstantTimeCurve};
+    use {Curve, AffinePoint, JacobianPoint};
 
     #[test]
     fn double() {
@@ -152,6 +178,32 @@ mod tests {
         assert_eq!(np, (537613624567015, 945163207984607).into());
     }
 
+    #[test]
+    fn on_curve() {
+        assert_eq!(U64Curve::b(), 14.into());
+        assert_eq!(U64KoblitzCurve::b(), 41.into());
+
+        let p = U64Curve::generator() + U64Curve::generator();
+        assert!(p.is_on_curve());
+        assert!(AffinePoint::<U64Curve>::infinity().is_on_curve());
+        assert!(!AffinePoint::<U64Curve>::from((2, 7)).is_on_curve());
+
+        let jp: JacobianPoint<U64MontgomeryCurve> = U64MontgomeryCurve::generator().into();
+        assert!((jp.clone() * 7u64 + jp.clone()).is_on_curve());
+        assert!(!JacobianPoint::<U64MontgomeryCurve>::from((2, 6, 3)).is_on_curve());
+    }
+
+    #[test]
+    fn y_from_x() {
+        use field::FieldValue;
+
+        // y^2 = x^3 + ax + b
+        let (x, y) = (U64Curve::generator() * 5).into_parts();
+        let root = ((x.squared() + U64Curve::a()) * x + U64Curve::b()).sqrt().unwrap();
+
+        assert!(root == y || root == -y);
+    }
+
     #[test]
     fn double_mont() {
         let p = U64MontgomeryCurve::generator() + U64MontgomeryCurve::generator();
@@ -168,6 +220,13 @@ mod tests {
         assert_eq!(bp, (210159848059198, 473433224346301).into());
     }
 
+    #[test]
+    fn mul_ct() {
+        let p = U64ConstantTimeCurve::generator();
+        assert_eq!(p.clone().mul_ct(570768668753918u64), (210159848059198, 473433224346301).into());
+        assert_eq!(p.mul_ct(0u64), AffinePoint::infinity());
+    }
+
     #[test]
     fn mul_montgomery() {
         let p = U64MontgomeryCurve::generator();
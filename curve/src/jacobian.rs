@@ -1,6 +1,7 @@
-use std::ops::{Mul, Add};
+use std::mem;
+use std::ops::{Mul, Add, AddAssign};
 
-use field::{FieldValue, Scalar};
+use field::{FieldValue, Scalar, Choice, ConditionallySelectable, ConstantTimeEq, batch_invert};
 use {Curve, AffinePoint};
 
 /// Point on the curve C in jacobian representation
@@ -16,8 +17,8 @@ impl<C: Curve> From<AffinePoint<C>> for Point<C> {
         let (x, y) = p.into_parts();
 
         Point {
-            x: x,
-            y: y,
+            x,
+            y,
             z: C::Value::one(),
         }
     }
@@ -54,6 +55,133 @@ impl<C: Curve> Point<C> {
     pub fn is_infinity(&self) -> bool {
         self.z == C::Value::zero()
     }
+
+    /// Test if point satisfies the curve equation y^2 = x^3 + a * x * z^4 + b * z^6 (point at infinity included)
+    pub fn is_on_curve(&self) -> bool {
+        if self.is_infinity() {
+            return true;
+        }
+
+        let z2 = self.z.squared();
+        let z4 = z2.squared();
+        self.y.squared() == (self.x.squared() + C::a() * z4) * self.x + C::b() * z4 * z2
+    }
+
+    /// Convert points to affine representation sharing a single field inversion
+    pub fn batch_into_affine(points: Vec<Self>) -> Vec<AffinePoint<C>> {
+        let mut z_inverses: Vec<C::Value> = points.iter().map(|p| p.z).collect();
+        batch_invert(&mut z_inverses);
+
+        points.into_iter().zip(z_inverses).map(|(p, z_inv)| {
+            if p.is_infinity() {
+                return AffinePoint::infinity();
+            }
+
+            let z_inv2 = z_inv.squared();
+            (p.x * z_inv2, p.y * z_inv2 * z_inv).into()
+        }).collect()
+    }
+
+    /// Doubling of (X, Y, Z), `z1z1` is Z^2
+    fn double(x1: C::Value, y1: C::Value, z1: C::Value, z1z1: C::Value) -> Self {
+        // S = 4*X*Y^2
+        let s = x1 * 4 * y1.squared();
+
+        // M = 3*X^2 + a*Z^4
+        // Curve over montgomery field elements should have C::a() in montgomery form!
+        let m = x1.squared() * 3 + C::a() * z1z1.squared();
+
+        // X' = M^2 - 2*S
+        let x = m.squared() - s * 2;
+
+        // Y' = M*(S - X') - 8*Y^4
+        let y = m * (s - x) - y1.squared().squared() * 8;
+
+        // Z' = 2*Y*Z
+        let z = (y1 * z1) * 2;
+
+        (x, y, z).into()
+    }
+
+    /// Addition of distinct points, given U1, U2, S1, S2 and both Z
+    fn chord(u1: C::Value, u2: C::Value, s1: C::Value, s2: C::Value, z1: C::Value, z2: C::Value) -> Self {
+        // H = U2 - U1
+        let h = u2 - u1;
+        // R = S2 - S1
+        let r = s2 - s1;
+
+        let h2 = h.squared();
+        let h3 = h2 * h;
+
+        // X3 = R^2 - H^3 - 2*U1*H^2
+        let x3 = r.squared() - h3 - u1*h2 * 2;
+
+        // Y3 = R*(U1*H^2 - X3) - S1*H^3
+        let y3 = r * (u1 * h2 - x3) - s1 * h3;
+
+        // Z3 = H*Z1*Z2
+        let z3 = h * z1 * z2;
+
+        (x3, y3, z3).into()
+    }
+}
+
+impl<C: Curve> Point<C> where C::Value: ConditionallySelectable + ConstantTimeEq {
+    /// Test if point is at infinity, in constant time
+    pub fn ct_is_infinity(&self) -> Choice {
+        self.z.ct_is_zero()
+    }
+
+    /// Add points without branching on them
+    ///
+    /// Both the chord and the tangent are computed, and the result is selected among them,
+    /// the point at infinity and the operands, which covers P + P, P + (-P) and additions
+    /// of the point at infinity.
+    pub fn add_ct(self, other: Self) -> Self {
+        let (self_infinity, other_infinity) = (self.ct_is_infinity(), other.ct_is_infinity());
+
+        let (x1, y1, z1) = self.clone().into_parts();
+        let (x2, y2, z2) = other.clone().into_parts();
+
+        let z1z1 = z1.squared();
+        let z2z2 = z2.squared();
+        let (u1, u2) = (x1 * z2z2, x2 * z1z1);
+        let (s1, s2) = (y1 * (z2z2 * z2), y2 * (z1z1 * z1));
+        let (same_x, same_y) = (u1.ct_eq(&u2), s1.ct_eq(&s2));
+
+        let mut r = Self::chord(u1, u2, s1, s2, z1, z2);
+        r.conditional_assign(&Self::double(x1, y1, z1, z1z1), same_x & same_y);
+        r.conditional_assign(&Self::infinity(), same_x & !same_y);
+        r.conditional_assign(&self, other_infinity);
+        r.conditional_assign(&other, self_infinity);
+        r
+    }
+
+    /// Multiply by scalar with a montgomery ladder, without branching on its bits
+    ///
+    /// Every bit costs one addition and one doubling, both done with `add_ct`.
+    pub fn mul_ct<I: Scalar>(self, other: I) -> Self {
+        let mut r0 = Self::infinity();
+        let mut r1 = self;
+        for i in (0..I::max_bits()).rev() {
+            let b = Choice::from(other.bit(i));
+            Self::conditional_swap(&mut r0, &mut r1, b);
+            r1 = r1.add_ct(r0.clone());
+            r0 = r0.clone().add_ct(r0);
+            Self::conditional_swap(&mut r0, &mut r1, b);
+        }
+        r0
+    }
+}
+
+impl<C: Curve> ConditionallySelectable for Point<C> where C::Value: ConditionallySelectable {
+    fn conditional_select(a: &Self, b: &Self, choice: Choice) -> Self {
+        Point {
+            x: C::Value::conditional_select(&a.x, &b.x, choice),
+            y: C::Value::conditional_select(&a.y, &b.y, choice),
+            z: C::Value::conditional_select(&a.z, &b.z, choice),
+        }
+    }
 }
 
 impl<I: Scalar, C: Curve> Mul<I> for Point<C>
@@ -65,8 +193,8 @@ impl<I: Scalar, C: Curve> Mul<I> for Point<C>
         let mut r1 = self;
         for i in 0..I::max_bits() {
             let b = Scalar::bit(&other, i);
-            if b { r0 = r0 + r1.clone() }
-            r1 = r1.clone() + r1;
+            if b { r0 += r1.clone() }
+            r1 += r1.clone();
         }
         r0
     }
@@ -88,64 +216,39 @@ impl<I: Into<Point<C>>, C: Curve> Add<I> for Point<C>
         let (x1, y1, z1) = self.into_parts();
         let (x2, y2, z2) = other.into_parts();
 
+        let z1z1 = z1.squared();
+        let z2z2 = z2.squared();
+
         // U1 = X1*Z2^2
-        let u1 = x1 * z2.squared();
+        let u1 = x1 * z2z2;
         // U2 = X2*Z1^2
-        let u2 = x2 * z1.squared();
+        let u2 = x2 * z1z1;
 
         // S1 = Y1*Z2^3
-        let s1 = y1 * (z2.squared() * z2);
+        let s1 = y1 * (z2z2 * z2);
         // S2 = Y2*Z1^3
-        let s2 = y2 * (z1.squared() * z1);
+        let s2 = y2 * (z1z1 * z1);
 
         if u1 == u2 {
             if s1 != s2 { return Self::infinity(); }
-            else {
-                // S = 4*X*Y^2
-                let s = x1 * 4 * y1.squared();
-
-                // M = 3*X^2 + a*Z^4
-                // Curve over montgomery field elements should have C::a() in montgomery form!
-                let m = x1.squared() * 3 + C::a() * (z1.squared().squared());
-
-                // X' = M^2 - 2*S
-                let x = m.squared() - s * 2;
-
-                // Y' = M*(S - X') - 8*Y^4
-                let y = m * (s - x) - y1.squared().squared() * 8;
-
-                // Z' = 2*Y*Z
-                let z = (y1 * z1) * 2;
-
-                return (x, y, z).into()
-            }
+            else { return Self::double(x1, y1, z1, z1z1); }
         }
 
-        // H = U2 - U1
-        let h = u2 - u1;
-        // R = S2 - S1
-        let r = s2 - s1;
-
-        let h2 = h.squared();
-        let h3 = h2 * h;
-
-        // X3 = R^2 - H^3 - 2*U1*H^2
-        let x3 = r.squared() - h3 - u1*h2 * 2;
-
-        // Y3 = R*(U1*H^2 - X3) - S1*H^3
-        let y3 = r * (u1 * h2 - x3) - s1 * h3;
-
-        // Z3 = H*Z1*Z2
-        let z3 = h * z1 * z2;
+        Self::chord(u1, u2, s1, s2, z1, z2)
+    }
+}
 
-        (x3, y3, z3).into()
+impl<I: Into<Point<C>>, C: Curve> AddAssign<I> for Point<C> {
+    fn add_assign(&mut self, other: I) {
+        let p = mem::replace(self, Self::infinity());
+        *self = p + other;
     }
 }
 
 #[cfg(test)]
 mod tests {
 
-    use test::{U64Curve, U64MontgomeryCurve};
+    use test::{U64Curve, U64MontgomeryCurve, U64ConstantTimeCurve};
     use {JacobianPoint, AffinePoint, Curve};
 
     #[test]
@@ -169,16 +272,52 @@ mod tests {
     #[test]
     fn mul() {
         let jp: JacobianPoint<U64Curve> = U64Curve::generator().into();
-        let dp = AffinePoint::from(jp.clone() * 2);;
+        let dp = AffinePoint::from(jp.clone() * 2);
         assert_eq!(dp, (570768668753918, 222182780873386).into());
         let bp = AffinePoint::from(jp * 570768668753918);
         assert_eq!(bp, (210159848059198, 473433224346301).into());
     }
 
+    #[test]
+    fn batch_into_affine() {
+        let jp: JacobianPoint<U64MontgomeryCurve> = U64MontgomeryCurve::generator().into();
+        let points = vec![jp.clone() * 2, JacobianPoint::infinity(), jp.clone() * 3, jp.clone()];
+
+        let expected: Vec<AffinePoint<_>> = points.iter().cloned().map(Into::into).collect();
+        assert_eq!(JacobianPoint::batch_into_affine(points), expected);
+    }
+
+    #[test]
+    fn mul_ct() {
+        let jp: JacobianPoint<U64MontgomeryCurve> = U64MontgomeryCurve::generator().into();
+        assert_eq!(AffinePoint::from(jp.clone().mul_ct(570768668753918u64)), AffinePoint::from(jp.clone() * 570768668753918u64));
+        assert!(jp.mul_ct(0u64).is_infinity());
+
+        let jp: JacobianPoint<U64ConstantTimeCurve> = U64ConstantTimeCurve::generator().into();
+        let bp = AffinePoint::from(jp.mul_ct(570768668753918u64));
+        assert_eq!(bp, (210159848059198, 473433224346301).into());
+    }
+
+    #[test]
+    fn add_ct_exceptional() {
+        let jp: JacobianPoint<U64ConstantTimeCurve> = U64ConstantTimeCurve::generator().into();
+        let dp = jp.clone() * 2u64;
+        let (x, y, z) = jp.clone().into_parts();
+        let neg: JacobianPoint<_> = (x, -y, z).into();
+        let infinity = JacobianPoint::infinity();
+
+        assert_eq!(jp.clone().add_ct(dp.clone()), jp.clone() + dp.clone());
+        assert_eq!(jp.clone().add_ct(jp.clone()), dp);
+        assert_eq!(jp.clone().add_ct(infinity.clone()), jp);
+        assert_eq!(infinity.clone().add_ct(jp.clone()), jp);
+        assert_eq!(infinity.clone().add_ct(infinity.clone()), infinity);
+        assert!(bool::from(jp.add_ct(neg).ct_is_infinity()));
+    }
+
     #[test]
     fn mul_mont() {
         let jp: JacobianPoint<U64MontgomeryCurve> = U64MontgomeryCurve::generator().into();
-        let dp = AffinePoint::from(jp.clone() * 2);;
+        let dp = AffinePoint::from(jp.clone() * 2);
         assert_eq!(dp, (570768668753918, 222182780873386).into());
         let bp = AffinePoint::from(jp * 570768668753918);
         assert_eq!(bp, (210159848059198, 473433224346301).into());
@@ -3,9 +3,12 @@
 #![warn(missing_docs)]
 
 extern crate ng_field as field;
+#[cfg(feature = "serde")] extern crate serde;
 
 mod affine;
 mod jacobian;
+#[cfg(feature = "serde")]
+mod serialization;
 
 #[cfg(test)]
 mod test;
@@ -13,14 +16,22 @@ mod test;
 pub use affine::Point as AffinePoint;
 pub use jacobian::Point as JacobianPoint;
 
+use field::FieldValue;
+
 /// Generalization of a y^2 = x^2 + ax + b curve
 pub trait Curve : Sized + Clone {
     /// Field element type of the curve
-    type Value: field::FieldValue;
+    type Value: FieldValue;
 
     /// Generator point of the curve
     fn generator() -> affine::Point<Self>;
 
     /// a-parameter of the curve
     fn a() -> Self::Value;
+
+    /// b-parameter of the curve, derived from the generator by default
+    fn b() -> Self::Value {
+        let (x, y) = Self::generator().into_parts();
+        y.squared() - (x.squared() + Self::a()) * x
+    }
 }
\ No newline at end of file
@@ -0,0 +1,81 @@
+//! Serde support: points are tuples of coordinates, validated to be on the curve
+
+use serde::{Serialize, Serializer, Deserialize, Deserializer};
+use serde::de::Error;
+
+use {Curve, AffinePoint, JacobianPoint};
+
+impl<C: Curve> Serialize for AffinePoint<C> where C::Value: Serialize {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        (self.x(), self.y()).serialize(serializer)
+    }
+}
+
+impl<'de, C: Curve> Deserialize<'de> for AffinePoint<C> where C::Value: Deserialize<'de> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let (x, y) = <(C::Value, C::Value)>::deserialize(deserializer)?;
+        let point = AffinePoint::new(x, y);
+
+        if !point.is_on_curve() {
+            return Err(D::Error::custom("point is not on the curve"));
+        }
+        Ok(point)
+    }
+}
+
+impl<C: Curve> Serialize for JacobianPoint<C> where C::Value: Serialize {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.clone().into_parts().serialize(serializer)
+    }
+}
+
+impl<'de, C: Curve> Deserialize<'de> for JacobianPoint<C> where C::Value: Deserialize<'de> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let point: JacobianPoint<C> = <(C::Value, C::Value, C::Value)>::deserialize(deserializer)?.into();
+
+        if !point.is_on_curve() {
+            return Err(D::Error::custom("point is not on the curve"));
+        }
+        Ok(point)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    extern crate serde_json;
+    extern crate ciborium;
+
+    use {Curve, AffinePoint, JacobianPoint};
+    use test::{U64Curve, U64MontgomeryCurve};
+
+    #[test]
+    fn affine_json() {
+        let p = U64Curve::generator();
+        let json = serde_json::to_string(&p).unwrap();
+
+        assert_eq!(json, "[\"0000000000000002\",\"0000000000000006\"]");
+        assert_eq!(serde_json::from_str::<AffinePoint<U64Curve>>(&json).unwrap(), p);
+
+        // (2, 7) is not on the curve
+        assert!(serde_json::from_str::<AffinePoint<U64Curve>>("[\"0000000000000002\",\"0000000000000007\"]").is_err());
+
+        let infinity = serde_json::to_string(&AffinePoint::<U64Curve>::infinity()).unwrap();
+        assert!(serde_json::from_str::<AffinePoint<U64Curve>>(&infinity).unwrap().is_infinity());
+    }
+
+    #[test]
+    fn jacobian_cbor() {
+        let p: JacobianPoint<U64MontgomeryCurve> = U64MontgomeryCurve::generator().into();
+        let p = p.clone() + p * 5u64;
+
+        let mut buf = Vec::new();
+        ciborium::ser::into_writer(&p, &mut buf).unwrap();
+        assert_eq!(ciborium::de::from_reader::<JacobianPoint<U64MontgomeryCurve>, _>(&buf[..]).unwrap(), p);
+
+        let (x, y, z) = p.into_parts();
+        let mut buf = Vec::new();
+        ciborium::ser::into_writer(&(x, y + y, z), &mut buf).unwrap();
+        assert!(ciborium::de::from_reader::<JacobianPoint<U64MontgomeryCurve>, _>(&buf[..]).is_err());
+    }
+}
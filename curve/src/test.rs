@@ -1,4 +1,4 @@
-use field;
+use field::{self, Field};
 use {Curve, AffinePoint};
 
 #[derive(Clone, Copy, PartialEq, Debug)]
@@ -8,8 +8,12 @@ impl field::Field for U64Field {
     type Value = u64;
 
     const MODULUS: u64 = 1125899839733759;
-    const R: u64 = 1099511644160;
-    const R_INVERSE: u64 = 343597359104;
+}
+
+impl field::MontgomeryField for U64Field {
+    const R: u64 = field::montgomery_r([Self::MODULUS])[0];
+    const R_INVERSE: u64 = field::montgomery_r_inverse([Self::MODULUS])[0];
+    const INV: u64 = field::montgomery_inv([Self::MODULUS]);
 }
 
 #[derive(Clone, Copy, PartialEq, Debug)]
@@ -58,4 +62,20 @@ impl Curve for U64MontgomeryCurve {
     fn a() -> Self::Value {
         7.into()
     }
-}
\ No newline at end of file
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct U64ConstantTimeCurve;
+
+/// y^2 = x^3 + 7x + 14 over Fp mod 1125899839733759, constant-time arithmetic
+impl Curve for U64ConstantTimeCurve {
+    type Value = field::ConstantTimeElement<U64Field>;
+
+    fn generator() -> AffinePoint<Self> {
+        (2, 6).into()
+    }
+
+    fn a() -> Self::Value {
+        7.into()
+    }
+}
@@ -1,5 +1,9 @@
 //! Abstract arithmetic
 
+use std::fmt;
+
+use text::{self, ParseError};
+
 /// Modular multiplication.
 pub trait ModMul<T=Self> {
     /// Multiplication of self by other of the same type
@@ -24,10 +28,40 @@ pub trait ModInv {
     fn inv(self, module: Self) -> Self;
 }
 
+/// Constant-time modular multiplicative inverse, Bernstein–Yang safegcd
+pub trait ModInvCt {
+    /// self^-1 mod module for self < module and the odd module, zero is mapped to zero
+    ///
+    /// The running time depends on the module only, so the input must already be reduced.
+    fn inv_ct(self, module: Self) -> Self;
+}
+
+/// Jacobi symbol
+pub trait Jacobi {
+    /// Jacobi symbol (self / module) for the odd module, -1, 0 or 1
+    ///
+    /// Equals to the Legendre symbol when the module is prime.
+    fn jacobi(self, module: Self) -> i8;
+}
+
 /// Modular multiplication with reduction
 pub trait MulReduce {
-    /// Modular multiplication followed by reduction
-    fn mul_reduce(self, other: Self, module: Self, r_inverse: Self) -> Self;
+    /// Modular multiplication followed by reduction (self * other * R^-1 mod module)
+    ///
+    /// Multi-limb implementations use word-by-word montgomery reduction with R = 2^(64*limbs) and
+    /// `inv` = -module^-1 mod 2^64, others can just multiply by `r_inverse`.
+    fn mul_reduce(self, other: Self, module: Self, r_inverse: Self, inv: u64) -> Self;
+}
+
+/// Modular multiplication with Barrett reduction
+pub trait BarrettMul {
+    /// self * other mod module, reducing the product with multiplications only
+    ///
+    /// `mu` is floor(2^(2k) / module) - 2^k, where k is the bit length of the module: the top
+    /// bit of the Barrett constant is implicit so that it fits the value type. The quotient is
+    /// estimated as q = x1 + (x1 * mu >> k) with x1 = x >> k, which is at most 3 below the
+    /// exact one, so few final subtractions are needed.
+    fn mul_barrett(self, other: Self, module: Self, mu: Self) -> Self;
 }
 
 pub struct BitsIterator<'a, T: 'a + Scalar> {
@@ -61,6 +95,7 @@ pub trait Scalar:
     ModNeg +
     ModInv +
     MulReduce +
+    BarrettMul +
     ::std::ops::Rem<Output=Self>
 {
     /// Multiplicative identity
@@ -75,10 +110,54 @@ pub trait Scalar:
     /// Get total bit
     fn max_bits() -> usize;
 
+    /// Write big-endian bytes of the value, `bytes` should be `max_bits() / 8` long
+    fn write_be_bytes(&self, bytes: &mut [u8]);
+
+    /// Read value from big-endian bytes, `bytes` should be `max_bits() / 8` long
+    fn read_be_bytes(bytes: &[u8]) -> Self;
+
+    /// Uniformly distributed random value in [0, bound), bound should be non-zero
+    ///
+    /// Samples bit length of the bound and rejects values not less than it, so that
+    /// the expected number of attempts is below 2.
+    #[cfg(feature = "rand")]
+    fn random_below<R: ::rand::RngCore + ?Sized>(rng: &mut R, bound: Self) -> Self {
+        let bits = (0..Self::max_bits()).rev().find(|&i| bound.bit(i)).map_or(0, |i| i + 1);
+        let mut bytes = vec![0u8; Self::max_bits() / 8];
+        let skip = bytes.len() - bits.div_ceil(8);
+
+        loop {
+            rng.fill_bytes(&mut bytes[skip..]);
+            if bits % 8 != 0 {
+                bytes[skip] &= (1u8 << (bits % 8)) - 1;
+            }
+
+            let value = Self::read_be_bytes(&bytes);
+            if value % bound == value {
+                return value;
+            }
+        }
+    }
+
+    /// Parse decimal or `0x`-prefixed hex string
+    fn parse(s: &str) -> Result<Self, ParseError> {
+        text::parse(s)
+    }
+
+    /// Format as decimal, for `Display` implementations
+    fn fmt_decimal(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        text::fmt_decimal(*self, f)
+    }
+
+    /// Format as lowercase hex, for `LowerHex` implementations
+    fn fmt_lower_hex(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        text::fmt_lower_hex(*self, f)
+    }
+
     /// Get bits iterator
     fn bits<'a>(&'a self) -> BitsIterator<'a, Self> {
         BitsIterator {
-            value: &self,
+            value: self,
             position: Self::max_bits(),
         }
     }
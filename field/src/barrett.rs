@@ -0,0 +1,231 @@
+//! Field element with Barrett-reduced multiplication
+
+use std::fmt;
+use std::ops::{Add, Mul, Neg, Sub, Div};
+use std::str::FromStr;
+
+use arith::{self, Scalar, ModAdd, ModMul, ModNeg, ModInv, BarrettMul};
+use ct::{Choice, ConditionallySelectable, ConstantTimeEq};
+use {field, element, sqrt, encoding, text};
+
+/// Field element on the field F with value V, multiplied with Barrett reduction
+///
+/// Values are kept in regular form, so conversions are free, while multiplication avoids
+/// the full division of `FieldElement`. Suitable for moduli without montgomery parameters.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BarrettElement<F: field::BarrettField> {
+    value: F::Value,
+}
+
+impl<F: field::BarrettField> BarrettElement<F> {
+    /// New field element from regular element
+    pub fn from_element(t: element::FieldElement<F>) -> Self {
+        BarrettElement { value: t.into_value() }
+    }
+
+    /// Convert to regular element
+    pub fn into_element(self) -> element::FieldElement<F> {
+        self.value.into()
+    }
+
+    /// Deconstruct and return raw value
+    pub fn into_value(self) -> F::Value {
+        self.value
+    }
+
+    /// Fixed-width big-endian bytes of the value
+    pub fn to_bytes_be(&self) -> Vec<u8> {
+        encoding::encode(self.value, false)
+    }
+
+    /// Fixed-width little-endian bytes of the value
+    pub fn to_bytes_le(&self) -> Vec<u8> {
+        encoding::encode(self.value, true)
+    }
+
+    /// New element from fixed-width big-endian bytes, values not less than MODULUS are rejected
+    pub fn from_bytes_be(bytes: &[u8]) -> Result<Self, encoding::DecodeError> {
+        encoding::decode(bytes, F::MODULUS, false).map(|value| BarrettElement { value })
+    }
+
+    /// New element from fixed-width little-endian bytes, values not less than MODULUS are rejected
+    pub fn from_bytes_le(bytes: &[u8]) -> Result<Self, encoding::DecodeError> {
+        encoding::decode(bytes, F::MODULUS, true).map(|value| BarrettElement { value })
+    }
+}
+
+impl<F: field::BarrettField> Add for BarrettElement<F> {
+    type Output = Self;
+    fn add(self, other: Self) -> Self::Output {
+        BarrettElement { value: self.value.add(other.value, F::MODULUS) }
+    }
+}
+
+impl<F: field::BarrettField> Sub for BarrettElement<F> {
+    type Output = Self;
+    fn sub(self, other: Self) -> Self::Output {
+        BarrettElement { value: self.value.add(other.value.neg(F::MODULUS), F::MODULUS) }
+    }
+}
+
+impl<F: field::BarrettField> Neg for BarrettElement<F> {
+    type Output = Self;
+    fn neg(self) -> Self::Output {
+        BarrettElement { value: self.value.neg(F::MODULUS) }
+    }
+}
+
+impl<F: field::BarrettField> Mul for BarrettElement<F> {
+    type Output = Self;
+    fn mul(self, other: Self) -> Self::Output {
+        BarrettElement { value: self.value.mul_barrett(other.value, F::MODULUS, F::MU) }
+    }
+}
+
+impl<F: field::BarrettField> Mul<u32> for BarrettElement<F> {
+    type Output = Self;
+    fn mul(self, other: u32) -> Self {
+        BarrettElement { value: self.value.mul(other, F::MODULUS) }
+    }
+}
+
+impl<F: field::BarrettField> Div for BarrettElement<F> {
+    type Output = Self;
+    fn div(self, other: Self) -> Self::Output {
+        assert!(other.value != F::Value::zero(), "division by zero");
+        BarrettElement { value: self.value.mul_barrett(other.value.inv(F::MODULUS), F::MODULUS, F::MU) }
+    }
+}
+
+impl<F: field::BarrettField<Value=V>, V: arith::Scalar> From<V> for BarrettElement<F> {
+    fn from(v: V) -> Self {
+        BarrettElement {
+            value: v % F::MODULUS,
+        }
+    }
+}
+
+impl<F: field::BarrettField> FromStr for BarrettElement<F> {
+    type Err = text::ParseError;
+
+    /// Parse decimal or `0x`-prefixed hex value, values not less than MODULUS are rejected
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        text::parse_canonical(s, F::MODULUS).map(|value| BarrettElement { value })
+    }
+}
+
+impl<F: field::BarrettField> fmt::Display for BarrettElement<F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.value.fmt_decimal(f)
+    }
+}
+
+impl<F: field::BarrettField> fmt::LowerHex for BarrettElement<F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.value.fmt_lower_hex(f)
+    }
+}
+
+impl<F: field::BarrettField> field::FieldValue for BarrettElement<F> {
+    type Value = F::Value;
+
+    /// Multiplication identity
+    fn one() -> Self {
+        BarrettElement {
+            value: F::Value::one(),
+        }
+    }
+
+    /// Additive identity
+    fn zero() -> Self {
+        BarrettElement {
+            value: F::Value::zero(),
+        }
+    }
+
+    fn characteristic() -> F::Value {
+        F::MODULUS
+    }
+
+    fn sqrt(self) -> Option<Self> {
+        sqrt::sqrt(self, Self::one(), F::MODULUS)
+    }
+
+    fn legendre(self) -> i8 {
+        sqrt::legendre(self, Self::one(), F::MODULUS)
+    }
+
+    #[cfg(feature = "rand")]
+    fn random<R: ::rand::RngCore + ?Sized>(rng: &mut R) -> Self {
+        BarrettElement { value: F::Value::random_below(rng, F::MODULUS) }
+    }
+}
+
+impl<F: field::BarrettField> ConditionallySelectable for BarrettElement<F> where F::Value: ConditionallySelectable {
+    fn conditional_select(a: &Self, b: &Self, choice: Choice) -> Self {
+        BarrettElement { value: F::Value::conditional_select(&a.value, &b.value, choice) }
+    }
+}
+
+impl<F: field::BarrettField> ConstantTimeEq for BarrettElement<F> where F::Value: ConstantTimeEq {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.value.ct_eq(&other.value)
+    }
+}
+
+impl_field_ops!(impl<F: field::BarrettField> BarrettElement<F>);
+
+#[cfg(test)]
+mod tests {
+
+    use {BarrettElement, FieldElement, FieldValue};
+    use test::{Mod19Field, Mod1125899839733759Field, Mod18446744073709551557Field};
+
+    use quickcheck::TestResult;
+
+    #[test]
+    fn smoky() {
+        let elem1: BarrettElement<Mod19Field> = 6.into();
+        let elem2: BarrettElement<Mod19Field> = 16.into();
+
+        assert_eq!(elem1 + elem2, 3.into());
+        assert_eq!(elem1 - elem2, 9.into());
+        assert_eq!(-elem1, 13.into());
+        assert_eq!(elem1 * elem2, 1.into());
+        assert_eq!(elem2 / elem1, 9.into());
+        assert_eq!(elem1.pow(20), 17.into());
+        assert_eq!(elem2.sqrt().map(|x| x * x), Some(elem2));
+        assert_eq!(format!("{}", elem2), "16");
+    }
+
+    quickcheck! {
+        fn mul_equals_regular(x: u64, y: u64) -> bool {
+            let (x_b, y_b): (BarrettElement<Mod1125899839733759Field>, BarrettElement<Mod1125899839733759Field>) =
+                (x.into(), y.into());
+            let (x_e, y_e): (FieldElement<Mod1125899839733759Field>, FieldElement<Mod1125899839733759Field>) =
+                (x.into(), y.into());
+
+            (x_b * y_b).into_element() == x_e * y_e
+        }
+
+        fn mul_equals_regular_full_word(x: u64, y: u64) -> bool {
+            // 2^64 - 59, the quotient estimate is at its widest
+            let (x_b, y_b): (BarrettElement<Mod18446744073709551557Field>, BarrettElement<Mod18446744073709551557Field>) =
+                (x.into(), y.into());
+            let (x_e, y_e): (FieldElement<Mod18446744073709551557Field>, FieldElement<Mod18446744073709551557Field>) =
+                (x.into(), y.into());
+
+            (x_b * y_b).into_element() == x_e * y_e && (-x_b * -x_b).into_element() == x_e * x_e
+        }
+
+        fn number_div_by_self_equals_one(x: u64) -> TestResult {
+            let x_b: BarrettElement<Mod1125899839733759Field> = x.into();
+
+            if x_b == BarrettElement::zero() {
+                TestResult::discard()
+            } else {
+                TestResult::from_bool(x_b / x_b == BarrettElement::one())
+            }
+        }
+    }
+}
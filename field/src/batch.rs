@@ -0,0 +1,86 @@
+//! Batch operations on field elements
+
+use field::FieldValue;
+
+/// Invert all non-zero elements of the slice in place (Montgomery's trick)
+///
+/// Costs one inversion and 3(n-1) multiplications for n non-zero elements.
+/// Zero elements are left untouched, their indices are returned.
+pub fn batch_invert<T: FieldValue>(values: &mut [T]) -> Vec<usize> {
+    let mut zeros = Vec::new();
+    let mut indices = Vec::with_capacity(values.len());
+    // products[k] = product of the first k + 1 non-zero elements
+    let mut products: Vec<T> = Vec::with_capacity(values.len());
+
+    for (i, value) in values.iter().enumerate() {
+        if *value == T::zero() {
+            zeros.push(i);
+            continue;
+        }
+
+        let product = match products.last() {
+            Some(last) => *last * *value,
+            None => *value,
+        };
+        products.push(product);
+        indices.push(i);
+    }
+
+    let mut inv = match products.last() {
+        Some(last) => last.invert().expect("product of non-zero field elements is non-zero"),
+        None => return zeros,
+    };
+
+    for k in (1..indices.len()).rev() {
+        let value = values[indices[k]];
+        values[indices[k]] = inv * products[k - 1];
+        inv *= value;
+    }
+    values[indices[0]] = inv;
+
+    zeros
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::batch_invert;
+    use {FieldElement, MontgomeryElement, FieldValue};
+    use test::{Mod19Field, Mod1125899839733759Field};
+
+    #[test]
+    fn smoky() {
+        let mut values: Vec<FieldElement<Mod19Field>> = vec![6.into(), 0.into(), 16.into(), 1.into()];
+
+        assert_eq!(batch_invert(&mut values), vec![1]);
+        assert_eq!(values, vec![16.into(), 0.into(), 6.into(), 1.into()]);
+    }
+
+    #[test]
+    fn empty() {
+        let mut values: Vec<FieldElement<Mod19Field>> = vec![];
+        assert_eq!(batch_invert(&mut values), Vec::<usize>::new());
+
+        let mut values: Vec<FieldElement<Mod19Field>> = vec![0.into(), 0.into()];
+        assert_eq!(batch_invert(&mut values), vec![0, 1]);
+        assert_eq!(values, vec![FieldElement::zero(), FieldElement::zero()]);
+    }
+
+    quickcheck! {
+        fn batch_invert_equals_single(xs: Vec<u64>) -> bool {
+            let mut values: Vec<MontgomeryElement<Mod1125899839733759Field>> =
+                xs.iter().map(|x| (*x).into()).collect();
+            let original = values.clone();
+
+            let zeros = batch_invert(&mut values);
+
+            original.into_iter().zip(values).enumerate().all(|(i, (x, inv))| {
+                if x == FieldValue::zero() {
+                    zeros.contains(&i) && inv == x
+                } else {
+                    !zeros.contains(&i) && inv == MontgomeryElement::one() / x
+                }
+            })
+        }
+    }
+}
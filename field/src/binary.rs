@@ -0,0 +1,381 @@
+//! Binary extension fields GF(2^m)
+
+use std::ops::{Add, Mul, Neg, Sub, Div};
+
+use field::FieldValue;
+
+/// Maximum number of 64-bit words in element representation, enough for m <= 576
+const WORDS: usize = 9;
+
+/// Binary field GF(2^m) description, polynomial basis
+///
+/// Degrees above 576 are rejected at compile time:
+///
+/// ```compile_fail
+/// # use ng_field::{BinaryField, BinaryFieldElement, FieldValue};
+/// #[derive(Clone, Copy, Debug, PartialEq)]
+/// struct TooLarge;
+///
+/// impl BinaryField for TooLarge {
+///     const DEGREE: usize = 577;
+///     const REDUCTION: &'static [usize] = &[0];
+/// }
+///
+/// let _ = BinaryFieldElement::<TooLarge>::one();
+/// ```
+pub trait BinaryField: Copy + Clone + PartialEq + ::std::fmt::Debug {
+    /// Extension degree m (at most 576)
+    const DEGREE: usize;
+    /// Exponents of the lower terms of the irreducible reduction polynomial
+    ///
+    /// x^DEGREE + x^REDUCTION[0] + x^REDUCTION[1] + ...
+    const REDUCTION: &'static [usize];
+}
+
+/// Element of the binary field F, polynomial of degree < m over GF(2)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BinaryFieldElement<F: BinaryField> {
+    words: [u64; WORDS],
+    _field: ::std::marker::PhantomData<F>,
+}
+
+/// Carry-less multiplication of two words, returning (low, high) words of the product
+fn clmul(a: u64, b: u64) -> (u64, u64) {
+    let mut low = 0;
+    let mut high = 0;
+
+    for i in 0..64 {
+        if (b >> i) & 1 == 1 {
+            low ^= a << i;
+            if i > 0 {
+                high ^= a >> (64 - i);
+            }
+        }
+    }
+
+    (low, high)
+}
+
+/// Reduce polynomial modulo the reduction polynomial of F, bit by bit from the top
+fn reduce<F: BinaryField>(words: &mut [u64]) {
+    for bit in (F::DEGREE..words.len() * 64).rev() {
+        if (words[bit / 64] >> (bit % 64)) & 1 == 0 {
+            continue;
+        }
+
+        // x^bit = x^(bit - m) * (x^REDUCTION[0] + x^REDUCTION[1] + ...)
+        words[bit / 64] ^= 1 << (bit % 64);
+        for e in F::REDUCTION {
+            let target = bit - F::DEGREE + e;
+            words[target / 64] ^= 1 << (target % 64);
+        }
+    }
+}
+
+impl<F: BinaryField> BinaryFieldElement<F> {
+    /// Evaluated on use, fails the build for fields which do not fit the representation
+    const DEGREE_FITS: () = assert!(F::DEGREE <= 64 * WORDS, "binary field degree should be at most 576");
+
+    fn word_count() -> usize {
+        F::DEGREE.div_ceil(64)
+    }
+
+    /// New element from the little-endian words of polynomial coefficients (reduced if needed)
+    pub fn from_words(words: &[u64]) -> Self {
+        let mut buf = vec![0u64; words.len().max(Self::word_count())];
+        buf[..words.len()].copy_from_slice(words);
+        reduce::<F>(&mut buf);
+
+        let mut result = Self::zero();
+        result.words[..Self::word_count()].copy_from_slice(&buf[..Self::word_count()]);
+        result
+    }
+
+    /// Little-endian words of polynomial coefficients
+    pub fn into_words(self) -> Vec<u64> {
+        self.words[..Self::word_count()].to_vec()
+    }
+
+    /// x^(2^power), repeated squaring
+    pub fn square_times(self, power: usize) -> Self {
+        (0..power).fold(self, |acc, _| acc.squared())
+    }
+
+    /// Multiplicative inverse by Itoh–Tsujii, a^-1 = (a^(2^(m-1) - 1))^2, zero is mapped to zero
+    pub fn inverse(self) -> Self {
+        if F::DEGREE == 1 {
+            return self;
+        }
+
+        // beta = a^(2^k - 1), built by the addition chain on the bits of m - 1
+        let target = F::DEGREE - 1;
+        let top = 63 - (target as u64).leading_zeros() as usize;
+
+        let mut beta = self;
+        let mut k = 1;
+        for i in (0..top).rev() {
+            // a^(2^(2k) - 1) = (a^(2^k - 1))^(2^k) * a^(2^k - 1)
+            beta = beta.square_times(k) * beta;
+            k *= 2;
+
+            if (target >> i) & 1 == 1 {
+                // a^(2^(k+1) - 1) = (a^(2^k - 1))^2 * a
+                beta = beta.squared() * self;
+                k += 1;
+            }
+        }
+
+        beta.squared()
+    }
+}
+
+impl<F: BinaryField> From<u64> for BinaryFieldElement<F> {
+    fn from(v: u64) -> Self {
+        Self::from_words(&[v])
+    }
+}
+
+impl<F: BinaryField> Add for BinaryFieldElement<F> {
+    type Output = Self;
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn add(self, other: Self) -> Self {
+        // coefficients are added modulo 2
+        let mut result = self;
+        for (r, o) in result.words.iter_mut().zip(other.words.iter()) {
+            *r ^= *o;
+        }
+        result
+    }
+}
+
+impl<F: BinaryField> Sub for BinaryFieldElement<F> {
+    type Output = Self;
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn sub(self, other: Self) -> Self {
+        // characteristic 2
+        self + other
+    }
+}
+
+impl<F: BinaryField> Neg for BinaryFieldElement<F> {
+    type Output = Self;
+    fn neg(self) -> Self {
+        self
+    }
+}
+
+impl<F: BinaryField> Mul for BinaryFieldElement<F> {
+    type Output = Self;
+    fn mul(self, other: Self) -> Self {
+        let n = Self::word_count();
+        let mut product = [0u64; 2 * WORDS];
+
+        for i in 0..n {
+            for j in 0..n {
+                let (low, high) = clmul(self.words[i], other.words[j]);
+                product[i + j] ^= low;
+                product[i + j + 1] ^= high;
+            }
+        }
+
+        reduce::<F>(&mut product[..2 * n]);
+
+        let mut result = Self::zero();
+        result.words[..n].copy_from_slice(&product[..n]);
+        result
+    }
+}
+
+impl<F: BinaryField> Mul<u32> for BinaryFieldElement<F> {
+    type Output = Self;
+    fn mul(self, other: u32) -> Self {
+        // characteristic 2, k * a = (k mod 2) * a
+        if other & 1 == 1 { self } else { Self::zero() }
+    }
+}
+
+impl<F: BinaryField> Div for BinaryFieldElement<F> {
+    type Output = Self;
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn div(self, other: Self) -> Self {
+        assert!(other != Self::zero(), "division by zero");
+        self * other.inverse()
+    }
+}
+
+impl<F: BinaryField> FieldValue for BinaryFieldElement<F> {
+    type Value = u64;
+
+    fn characteristic() -> u64 {
+        2
+    }
+
+    fn degree() -> usize {
+        F::DEGREE
+    }
+
+    fn frobenius_map(self, power: usize) -> Self {
+        self.square_times(power % F::DEGREE)
+    }
+
+    fn sqrt(self) -> Option<Self> {
+        // every element is a square, sqrt(a) = a^(2^(m-1))
+        Some(self.square_times(F::DEGREE - 1))
+    }
+
+    fn legendre(self) -> i8 {
+        if self == Self::zero() { 0 } else { 1 }
+    }
+
+    #[cfg(feature = "rand")]
+    fn random<R: ::rand::RngCore + ?Sized>(rng: &mut R) -> Self {
+        // every polynomial of degree < m is an element
+        let mut result = Self::zero();
+        for word in result.words[..Self::word_count()].iter_mut() {
+            *word = rng.next_u64();
+        }
+        if F::DEGREE % 64 != 0 {
+            result.words[F::DEGREE / 64] &= (1 << (F::DEGREE % 64)) - 1;
+        }
+        result
+    }
+
+    fn one() -> Self {
+        let mut result = Self::zero();
+        result.words[0] = 1;
+        result
+    }
+
+    fn zero() -> Self {
+        let () = Self::DEGREE_FITS;
+
+        BinaryFieldElement {
+            words: [0; WORDS],
+            _field: ::std::marker::PhantomData,
+        }
+    }
+}
+
+impl_field_ops!(impl<F: BinaryField> BinaryFieldElement<F>);
+
+#[cfg(test)]
+mod tests {
+
+    use super::{BinaryField, BinaryFieldElement};
+    use FieldValue;
+
+    /// AES field, x^8 + x^4 + x^3 + x + 1
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    struct AesField;
+
+    impl BinaryField for AesField {
+        const DEGREE: usize = 8;
+        const REDUCTION: &'static [usize] = &[4, 3, 1, 0];
+    }
+
+    /// GHASH field, x^128 + x^7 + x^2 + x + 1
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    struct GhashField;
+
+    impl BinaryField for GhashField {
+        const DEGREE: usize = 128;
+        const REDUCTION: &'static [usize] = &[7, 2, 1, 0];
+    }
+
+    /// sect233, x^233 + x^74 + 1
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    struct Sect233Field;
+
+    impl BinaryField for Sect233Field {
+        const DEGREE: usize = 233;
+        const REDUCTION: &'static [usize] = &[74, 0];
+    }
+
+    /// sect283, x^283 + x^12 + x^7 + x^5 + 1
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    struct Sect283Field;
+
+    impl BinaryField for Sect283Field {
+        const DEGREE: usize = 283;
+        const REDUCTION: &'static [usize] = &[12, 7, 5, 0];
+    }
+
+    type Aes = BinaryFieldElement<AesField>;
+
+    #[test]
+    fn aes() {
+        let a: Aes = 0x57.into();
+        let b: Aes = 0x83.into();
+
+        // FIPS-197 examples
+        assert_eq!(a + b, 0xd4.into());
+        assert_eq!(a * b, 0xc1.into());
+        assert_eq!(a * Aes::from(0x13), 0xfe.into());
+
+        // S-box inverse pair
+        assert_eq!(Aes::from(0x53).inverse(), 0xca.into());
+        assert_eq!(Aes::from(0x53) * Aes::from(0xca), Aes::one());
+
+        assert_eq!(Aes::from(0x1b), Aes::from(0x100));
+        assert_eq!(Aes::zero().inverse(), Aes::zero());
+    }
+
+    #[test]
+    fn aes_all_inverses() {
+        for x in 1..256 {
+            let a = Aes::from(x);
+            assert_eq!(a * a.inverse(), Aes::one());
+            assert_eq!(a / a, Aes::one());
+            assert_eq!(a.pow(255), Aes::one());
+            assert_eq!(a.sqrt().unwrap().squared(), a);
+        }
+    }
+
+    #[test]
+    fn frobenius() {
+        let a = Aes::from(0x57);
+        assert_eq!(a.frobenius_map(1), a.squared());
+        assert_eq!(a.frobenius_map(8), a);
+        assert_eq!(a.frobenius_map(3), a.pow(8));
+    }
+
+    #[cfg(feature = "rand")]
+    #[test]
+    fn random() {
+        use rand::{SeedableRng, rngs::StdRng};
+
+        let mut rng = StdRng::seed_from_u64(233);
+        for _ in 0..100 {
+            let x = BinaryFieldElement::<Sect233Field>::random(&mut rng);
+            assert_eq!(BinaryFieldElement::from_words(&x.into_words()), x);
+            assert!(x.into_words()[3] >> 41 == 0);
+            assert!(Aes::random_nonzero(&mut rng) != Aes::zero());
+        }
+    }
+
+    quickcheck! {
+        fn ghash_inverse(a: u64, b: u64) -> bool {
+            let x = BinaryFieldElement::<GhashField>::from_words(&[a, b]);
+            x == FieldValue::zero() || x * x.inverse() == FieldValue::one()
+        }
+
+        fn sect233_inverse(a: u64, b: u64, c: u64, d: u64) -> bool {
+            let x = BinaryFieldElement::<Sect233Field>::from_words(&[a, b, c, d]);
+            x == FieldValue::zero() || x * x.inverse() == FieldValue::one()
+        }
+
+        fn sect283_distributive(a: Vec<u64>, b: Vec<u64>, c: Vec<u64>) -> bool {
+            let x = BinaryFieldElement::<Sect283Field>::from_words(&a);
+            let y = BinaryFieldElement::<Sect283Field>::from_words(&b);
+            let z = BinaryFieldElement::<Sect283Field>::from_words(&c);
+
+            x * (y + z) == x * y + x * z && (x * y) * z == x * (y * z)
+        }
+
+        fn sect283_inverse_and_sqrt(a: u64, b: u64, c: u64, d: u64, e: u64) -> bool {
+            let x = BinaryFieldElement::<Sect283Field>::from_words(&[a, b, c, d, e]);
+            (x == FieldValue::zero() || x * x.inverse() == FieldValue::one()) &&
+                x.sqrt().unwrap().squared() == x
+        }
+    }
+}
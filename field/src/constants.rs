@@ -0,0 +1,271 @@
+//! Compile-time derivation of montgomery and Barrett constants from the modulus limbs
+//!
+//! Limbs are 64-bit little-endian words, R = 2^(64 * limbs) mod MODULUS.
+
+/// a >= b
+const fn geq<const N: usize>(a: &[u64; N], b: &[u64; N]) -> bool {
+    let mut i = N;
+    while i > 0 {
+        i -= 1;
+        if a[i] != b[i] {
+            return a[i] > b[i];
+        }
+    }
+    true
+}
+
+/// a + b, returning the carry
+const fn add<const N: usize>(a: &[u64; N], b: &[u64; N]) -> ([u64; N], bool) {
+    let mut result = [0u64; N];
+    let mut carry = 0u128;
+    let mut i = 0;
+    while i < N {
+        let t = (a[i] as u128) + (b[i] as u128) + carry;
+        result[i] = t as u64;
+        carry = t >> 64;
+        i += 1;
+    }
+    (result, carry != 0)
+}
+
+/// a - b, wrapping
+const fn sub<const N: usize>(a: &[u64; N], b: &[u64; N]) -> [u64; N] {
+    let mut result = [0u64; N];
+    let mut borrow = 0u64;
+    let mut i = 0;
+    while i < N {
+        let (t, b1) = a[i].overflowing_sub(b[i]);
+        let (t, b2) = t.overflowing_sub(borrow);
+        result[i] = t;
+        borrow = (b1 || b2) as u64;
+        i += 1;
+    }
+    result
+}
+
+/// 2 * a mod p, a < p
+const fn double_mod<const N: usize>(a: &[u64; N], p: &[u64; N]) -> [u64; N] {
+    let (sum, carry) = add(a, a);
+    if carry || geq(&sum, p) { sub(&sum, p) } else { sum }
+}
+
+/// a / 2 mod p, a < p, p is odd
+const fn halve_mod<const N: usize>(a: &[u64; N], p: &[u64; N]) -> [u64; N] {
+    let (sum, carry) = if a[0] & 1 == 0 { (*a, false) } else { add(a, p) };
+
+    let mut result = [0u64; N];
+    let mut i = 0;
+    while i < N {
+        let high = if i + 1 < N { sum[i + 1] } else { carry as u64 };
+        result[i] = (sum[i] >> 1) | (high << 63);
+        i += 1;
+    }
+    result
+}
+
+/// 2^(64 * N) * a mod p, by doubling
+const fn shift_mod<const N: usize>(a: [u64; N], p: &[u64; N]) -> [u64; N] {
+    let mut result = a;
+    let mut i = 0;
+    while i < 64 * N {
+        result = double_mod(&result, p);
+        i += 1;
+    }
+    result
+}
+
+const fn one<const N: usize>() -> [u64; N] {
+    let mut result = [0u64; N];
+    result[0] = 1;
+    result
+}
+
+/// Montgomery coefficient R = 2^(64 * N) mod modulus
+pub const fn montgomery_r<const N: usize>(modulus: [u64; N]) -> [u64; N] {
+    shift_mod(one(), &modulus)
+}
+
+/// R^2 mod modulus, converts values into montgomery form by montgomery multiplication
+pub const fn montgomery_r2<const N: usize>(modulus: [u64; N]) -> [u64; N] {
+    shift_mod(montgomery_r(modulus), &modulus)
+}
+
+/// R^-1 mod modulus, modulus should be odd
+pub const fn montgomery_r_inverse<const N: usize>(modulus: [u64; N]) -> [u64; N] {
+    let mut result = one();
+    let mut i = 0;
+    while i < 64 * N {
+        result = halve_mod(&result, &modulus);
+        i += 1;
+    }
+    result
+}
+
+/// Montgomery reduction constant -modulus^-1 mod 2^64, modulus should be odd
+pub const fn montgomery_inv<const N: usize>(modulus: [u64; N]) -> u64 {
+    // Newton iteration, each step doubles the number of correct low bits
+    let mut inv = 1u64;
+    let mut i = 0;
+    while i < 6 {
+        inv = inv.wrapping_mul(2u64.wrapping_sub(modulus[0].wrapping_mul(inv)));
+        i += 1;
+    }
+    inv.wrapping_neg()
+}
+
+/// Barrett constant floor(2^(2k) / modulus) - 2^k, k is the bit length of modulus
+///
+/// The quotient lies in [2^k, 2^(k+1)) for moduli that are not a power of two, so dropping
+/// its top bit keeps the constant within the limbs.
+pub const fn barrett_mu<const N: usize>(modulus: [u64; N]) -> [u64; N] {
+    let mut k = 64 * N;
+    while k > 0 && (modulus[(k - 1) / 64] >> ((k - 1) % 64)) & 1 == 0 {
+        k -= 1;
+    }
+
+    // binary long division of 2^(2k), keeping quotient bits below k
+    let mut result = [0u64; N];
+    let mut remainder = [0u64; N];
+    let mut i = 2 * k + 1;
+    while i > 0 {
+        i -= 1;
+
+        let (mut doubled, carry) = add(&remainder, &remainder);
+        if i == 2 * k {
+            doubled[0] |= 1;
+        }
+
+        if carry || geq(&doubled, &modulus) {
+            remainder = sub(&doubled, &modulus);
+            if i < k {
+                result[i / 64] |= 1 << (i % 64);
+            }
+        } else {
+            remainder = doubled;
+        }
+    }
+    result
+}
+
+/// Test if hand-written R, R^-1 and reduction constant match the ones derived from modulus
+pub const fn montgomery_constants_valid<const N: usize>(
+    modulus: [u64; N],
+    r: [u64; N],
+    r_inverse: [u64; N],
+    inv: u64,
+) -> bool {
+    let expected_r = montgomery_r(modulus);
+    let expected_r_inverse = montgomery_r_inverse(modulus);
+
+    let mut i = 0;
+    while i < N {
+        if r[i] != expected_r[i] || r_inverse[i] != expected_r_inverse[i] {
+            return false;
+        }
+        i += 1;
+    }
+
+    inv == montgomery_inv(modulus)
+}
+
+/// Compile-time check of hand-written montgomery constants, given as limb arrays
+///
+/// ```
+/// #[macro_use] extern crate ng_field;
+///
+/// check_montgomery_constants!([1125899839733759], [1099511644160], [343597359104], 0x13fffffc000001);
+/// # fn main() {}
+/// ```
+#[macro_export]
+macro_rules! check_montgomery_constants {
+    ($modulus:expr, $r:expr, $r_inverse:expr, $inv:expr) => {
+        const _: () = assert!(
+            $crate::montgomery_constants_valid($modulus, $r, $r_inverse, $inv),
+            "montgomery constants are inconsistent with the modulus",
+        );
+    };
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    const P256: [u64; 4] = [0xffffffffffffffff, 0x00000000ffffffff, 0x0000000000000000, 0xffffffff00000001];
+
+    #[test]
+    fn p256() {
+        assert_eq!(
+            montgomery_r(P256),
+            [0x0000000000000001, 0xFFFFFFFF00000000, 0xFFFFFFFFFFFFFFFF, 0x00000000FFFFFFFE]
+        );
+        assert_eq!(
+            montgomery_r_inverse(P256),
+            [0x0000000300000000, 0x00000001FFFFFFFE, 0xFFFFFFFD00000002, 0xFFFFFFFE00000003]
+        );
+        // 2^512 mod p
+        assert_eq!(
+            montgomery_r2(P256),
+            [0x0000000000000003, 0xFFFFFFFBFFFFFFFF, 0xFFFFFFFFFFFFFFFE, 0x00000004FFFFFFFD]
+        );
+        assert_eq!(montgomery_inv(P256), 1);
+    }
+
+    #[test]
+    fn single_limb() {
+        assert_eq!(montgomery_r([1125899839733759]), [1099511644160]);
+        assert_eq!(montgomery_r_inverse([1125899839733759]), [343597359104]);
+        assert_eq!(montgomery_inv([1125899839733759]), 0x13fffffc000001);
+        assert_eq!(montgomery_inv([19]), 0x79435e50d79435e5);
+    }
+
+    #[test]
+    fn barrett() {
+        // 2^512 / p = 2^256 + 2^224 - 2^192 - 2^160 + ...
+        assert_eq!(
+            barrett_mu(P256),
+            [0x0000000000000003, 0xfffffffeffffffff, 0xfffffffefffffffe, 0x00000000ffffffff]
+        );
+        // 2^10 / 19 = 53 = 32 + 21
+        assert_eq!(barrett_mu([19]), [21]);
+    }
+
+    #[test]
+    fn validation() {
+        assert!(montgomery_constants_valid([1125899839733759], [1099511644160], [343597359104], 0x13fffffc000001));
+        assert!(!montgomery_constants_valid([1125899839733759], [1099511644160], [343597359105], 0x13fffffc000001));
+        assert!(!montgomery_constants_valid([19], [16], [6], 0x79435e50d79435e5));
+        assert!(montgomery_constants_valid([19], [17], [9], 0x79435e50d79435e5));
+    }
+
+    quickcheck! {
+        fn single_limb_constants(p: u64) -> bool {
+            let p = p | 1;
+            if p == 1 {
+                return true;
+            }
+
+            let r = montgomery_r([p])[0] as u128;
+            let r_inverse = montgomery_r_inverse([p])[0] as u128;
+
+            r == (1u128 << 64) % p as u128 &&
+                r * r_inverse % p as u128 == 1 &&
+                montgomery_r2([p])[0] as u128 == r * r % p as u128 &&
+                p.wrapping_mul(montgomery_inv([p])) == u64::MAX
+        }
+
+        fn single_limb_barrett(p: u64) -> bool {
+            let p = p | 1;
+            if p == 1 {
+                return true;
+            }
+
+            let k = 64 - p.leading_zeros();
+            let mu = (1u128 << k) + barrett_mu([p])[0] as u128;
+
+            // 2^(2k) - mu * p should be the remainder, wrapping 2^128 to zero for k = 64
+            let power = if k == 64 { 0 } else { 1u128 << (2 * k) };
+            power.wrapping_sub(mu * p as u128) < p as u128
+        }
+    }
+}
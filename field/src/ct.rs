@@ -0,0 +1,249 @@
+//! Constant-time selection, comparison and modular arithmetic
+//!
+//! Building blocks for code which must not branch or index memory on secret data.
+//! Regular element types keep their faster variable-time arithmetic, constant-time
+//! arithmetic is opt-in through [`ConstantTimeElement`](struct.ConstantTimeElement.html).
+
+use std::hint::black_box;
+use std::ops::{BitAnd, BitOr, BitXor, Not};
+
+use arith::ModInvCt;
+
+/// Secret boolean, 0 or 1, for constant-time selection
+///
+/// Unlike `bool` it is not meant to be branched on: the value passes an optimization
+/// barrier on construction, so that the compiler does not turn masks into branches.
+#[derive(Clone, Copy, Debug)]
+pub struct Choice(u8);
+
+impl Choice {
+    /// Underlying value, 0 or 1
+    pub fn unwrap_u8(&self) -> u8 {
+        self.0
+    }
+}
+
+impl From<u8> for Choice {
+    /// New choice from 0 or 1
+    fn from(value: u8) -> Self {
+        debug_assert!(value <= 1);
+        Choice(black_box(value))
+    }
+}
+
+impl From<bool> for Choice {
+    fn from(value: bool) -> Self {
+        Choice::from(value as u8)
+    }
+}
+
+impl From<Choice> for bool {
+    /// Leave constant-time domain, the result is fine to branch on
+    fn from(choice: Choice) -> bool {
+        choice.0 == 1
+    }
+}
+
+impl BitAnd for Choice {
+    type Output = Choice;
+    fn bitand(self, other: Choice) -> Choice {
+        Choice::from(self.0 & other.0)
+    }
+}
+
+impl BitOr for Choice {
+    type Output = Choice;
+    fn bitor(self, other: Choice) -> Choice {
+        Choice::from(self.0 | other.0)
+    }
+}
+
+impl BitXor for Choice {
+    type Output = Choice;
+    fn bitxor(self, other: Choice) -> Choice {
+        Choice::from(self.0 ^ other.0)
+    }
+}
+
+impl Not for Choice {
+    type Output = Choice;
+    fn not(self) -> Choice {
+        Choice::from(self.0 ^ 1)
+    }
+}
+
+/// Selection between two values without branching
+pub trait ConditionallySelectable: Sized + Clone {
+    /// `a` when choice is 0, `b` when it is 1
+    fn conditional_select(a: &Self, b: &Self, choice: Choice) -> Self;
+
+    /// Replace self with other when choice is 1
+    fn conditional_assign(&mut self, other: &Self, choice: Choice) {
+        *self = Self::conditional_select(self, other, choice);
+    }
+
+    /// Swap a and b when choice is 1
+    fn conditional_swap(a: &mut Self, b: &mut Self, choice: Choice) {
+        let t = a.clone();
+        a.conditional_assign(b, choice);
+        b.conditional_assign(&t, choice);
+    }
+}
+
+/// Equality test without branching
+pub trait ConstantTimeEq {
+    /// 1 when self equals other, 0 otherwise
+    fn ct_eq(&self, other: &Self) -> Choice;
+
+    /// 1 when self does not equal other, 0 otherwise
+    fn ct_ne(&self, other: &Self) -> Choice {
+        !self.ct_eq(other)
+    }
+}
+
+macro_rules! impl_unsigned {
+    ($t: ty) => {
+        impl ConditionallySelectable for $t {
+            fn conditional_select(a: &Self, b: &Self, choice: Choice) -> Self {
+                let mask = (choice.unwrap_u8() as $t).wrapping_neg();
+                a ^ ((a ^ b) & mask)
+            }
+        }
+
+        impl ConstantTimeEq for $t {
+            fn ct_eq(&self, other: &Self) -> Choice {
+                // the top bit of x | -x is set for non-zero x only
+                let x = (self ^ other) as u64;
+                Choice::from((((x | x.wrapping_neg()) >> 63) ^ 1) as u8)
+            }
+        }
+    }
+}
+
+impl_unsigned!(u8);
+impl_unsigned!(u32);
+impl_unsigned!(u64);
+
+impl<T: ConditionallySelectable + Copy, const N: usize> ConditionallySelectable for [T; N] {
+    fn conditional_select(a: &Self, b: &Self, choice: Choice) -> Self {
+        let mut result = *a;
+        for (r, b) in result.iter_mut().zip(b.iter()) {
+            r.conditional_assign(b, choice);
+        }
+        result
+    }
+}
+
+impl<T: ConstantTimeEq, const N: usize> ConstantTimeEq for [T; N] {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.iter().zip(other.iter()).fold(Choice::from(1), |acc, (a, b)| acc & a.ct_eq(b))
+    }
+}
+
+/// Modular arithmetic without secret-dependent branches or memory access
+///
+/// Operands should be reduced, only the modulus is treated as public. Inversion comes from
+/// the `ModInvCt` supertrait.
+pub trait ConstantTimeArith: ConditionallySelectable + ConstantTimeEq + ModInvCt {
+    /// self + other mod module
+    fn add_ct(self, other: Self, module: Self) -> Self;
+
+    /// self - other mod module
+    fn sub_ct(self, other: Self, module: Self) -> Self;
+
+    /// self * other * R^-1 mod module, R = 2^(64 * limbs) and `inv` = -module^-1 mod 2^64
+    fn mul_reduce_ct(self, other: Self, module: Self, inv: u64) -> Self;
+}
+
+impl ConstantTimeArith for u64 {
+    fn add_ct(self, other: Self, module: Self) -> Self {
+        let (sum, carry) = self.overflowing_add(other);
+        let (reduced, borrow) = sum.overflowing_sub(module);
+
+        // keep the sum only when it neither wrapped nor reached the modulus
+        let keep = Choice::from(borrow as u8) & !Choice::from(carry as u8);
+        u64::conditional_select(&reduced, &sum, keep)
+    }
+
+    fn sub_ct(self, other: Self, module: Self) -> Self {
+        let (diff, borrow) = self.overflowing_sub(other);
+        diff.wrapping_add(module & (borrow as u64).wrapping_neg())
+    }
+
+    fn mul_reduce_ct(self, other: Self, module: Self, inv: u64) -> Self {
+        let t = (self as u128) * (other as u128);
+        let k = (t as u64).wrapping_mul(inv);
+
+        // t + k * module < 2^129, the low word vanishes
+        let (sum, carry) = t.overflowing_add((k as u128) * (module as u128));
+        let r = (sum >> 64) as u64;
+
+        // r + carry * 2^64 < 2 * module, final subtraction
+        let (reduced, borrow) = r.overflowing_sub(module);
+        let keep = Choice::from(borrow as u8) & !Choice::from(carry as u8);
+        u64::conditional_select(&reduced, &r, keep)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::{Choice, ConditionallySelectable, ConstantTimeEq, ConstantTimeArith};
+    use arith::{ModAdd, ModMul, ModNeg, ModInv, ModInvCt};
+    use field::{Field, MontgomeryField};
+    use test::Mod1125899839733759Field;
+
+    #[test]
+    fn choice() {
+        let (yes, no) = (Choice::from(1), Choice::from(0));
+
+        assert!(bool::from(yes & !no));
+        assert!(!bool::from(yes & no));
+        assert!(bool::from(yes ^ no));
+        assert!(bool::from(no | yes));
+        assert!(bool::from(5u64.ct_eq(&5)));
+        assert!(bool::from(5u64.ct_ne(&6)));
+        assert!(bool::from([1u64, u64::MAX].ct_ne(&[1, 0])));
+    }
+
+    #[test]
+    fn select_swap() {
+        let (mut a, mut b) = (3u64, u64::MAX);
+
+        assert_eq!(u64::conditional_select(&a, &b, Choice::from(0)), 3);
+        assert_eq!(u64::conditional_select(&a, &b, Choice::from(1)), u64::MAX);
+
+        u64::conditional_swap(&mut a, &mut b, Choice::from(0));
+        assert_eq!((a, b), (3, u64::MAX));
+        u64::conditional_swap(&mut a, &mut b, Choice::from(1));
+        assert_eq!((a, b), (u64::MAX, 3));
+
+        assert_eq!(<[u64; 2]>::conditional_select(&[1, 2], &[3, 4], Choice::from(1)), [3, 4]);
+    }
+
+    quickcheck! {
+        fn equals_regular(x: u64, y: u64) -> bool {
+            let p = Mod1125899839733759Field::MODULUS;
+            let (x, y) = (x % p, y % p);
+            let r_inverse = Mod1125899839733759Field::R_INVERSE;
+            let inv = Mod1125899839733759Field::INV;
+
+            x.add_ct(y, p) == ModAdd::add(x, y, p) &&
+                x.sub_ct(y, p) == ModAdd::add(x, ModNeg::neg(y, p), p) &&
+                x.mul_reduce_ct(y, p, inv) == ModMul::mul(ModMul::mul(x, y, p), r_inverse, p) &&
+                x.inv_ct(p) == x.inv(p)
+        }
+
+        fn equals_regular_full_word(x: u64, y: u64) -> bool {
+            // 2^64 - 59, sums and montgomery products overflow the word
+            let p = 18446744073709551557;
+            let (x, y) = (x % p, y % p);
+            let inv = ::montgomery_inv([p]);
+            let r_inverse = ::montgomery_r_inverse([p])[0];
+
+            x.add_ct(y, p) == ModAdd::add(x, y, p) &&
+                x.sub_ct(y, p) == ModAdd::add(x, ModNeg::neg(y, p), p) &&
+                x.mul_reduce_ct(y, p, inv) == ModMul::mul(ModMul::mul(x, y, p), r_inverse, p)
+        }
+    }
+}
@@ -0,0 +1,233 @@
+//! Montgomery field element with constant-time arithmetic
+
+use std::fmt;
+use std::ops::{Add, Mul, Neg, Sub, Div};
+
+use arith::{Scalar, ModMul, ModInvCt};
+use ct::{Choice, ConditionallySelectable, ConstantTimeEq, ConstantTimeArith};
+use field::FieldValue;
+use {field, element, mont, sqrt, power};
+
+/// Field element on the field F in montgomery representation, with constant-time arithmetic
+///
+/// Addition, subtraction, multiplication, division and `pow` do not branch or index memory
+/// on the values, division inverts with safegcd. Square root, Legendre symbol and conversions
+/// from unreduced values are still variable-time.
+///
+/// The field R should be 2^(64 * limbs) mod MODULUS, as derived by
+/// [`montgomery_r`](fn.montgomery_r.html); the representation then matches `MontgomeryElement`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ConstantTimeElement<F: field::MontgomeryField> {
+    value: F::Value,
+}
+
+impl<F: field::MontgomeryField> ConstantTimeElement<F> where F::Value: ConstantTimeArith {
+    /// New field element from regular form
+    pub fn from_element(t: element::FieldElement<F>) -> Self {
+        t.into_value().into()
+    }
+
+    /// Convert to regular form
+    pub fn into_element(self) -> element::FieldElement<F> {
+        self.into_reduced_value().into()
+    }
+
+    /// New field element from montgomery element, the representation is shared
+    pub fn from_montgomery(t: mont::MontgomeryElement<F>) -> Self {
+        ConstantTimeElement { value: t.into_value() }
+    }
+
+    /// Convert to montgomery element, the representation is shared
+    pub fn into_montgomery(self) -> mont::MontgomeryElement<F> {
+        mont::MontgomeryElement::from_raw(self.value)
+    }
+
+    /// Deconstruct and return raw value (not reduced)
+    pub fn into_value(self) -> F::Value {
+        self.value
+    }
+
+    /// Deconstruct and return reduced value
+    pub fn into_reduced_value(self) -> F::Value {
+        self.value.mul_reduce_ct(F::Value::one(), F::MODULUS, F::INV)
+    }
+
+    /// R^2 mod MODULUS, moves values into montgomery form
+    fn r2() -> F::Value {
+        ModMul::<F::Value>::mul(F::R, F::R, F::MODULUS)
+    }
+}
+
+impl<F: field::MontgomeryField> fmt::Display for ConstantTimeElement<F> where F::Value: ConstantTimeArith {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.into_reduced_value().fmt_decimal(f)
+    }
+}
+
+impl<F: field::MontgomeryField> Add for ConstantTimeElement<F> where F::Value: ConstantTimeArith {
+    type Output = Self;
+    fn add(self, other: Self) -> Self::Output {
+        ConstantTimeElement { value: self.value.add_ct(other.value, F::MODULUS) }
+    }
+}
+
+impl<F: field::MontgomeryField> Sub for ConstantTimeElement<F> where F::Value: ConstantTimeArith {
+    type Output = Self;
+    fn sub(self, other: Self) -> Self::Output {
+        ConstantTimeElement { value: self.value.sub_ct(other.value, F::MODULUS) }
+    }
+}
+
+impl<F: field::MontgomeryField> Neg for ConstantTimeElement<F> where F::Value: ConstantTimeArith {
+    type Output = Self;
+    fn neg(self) -> Self::Output {
+        ConstantTimeElement { value: F::Value::zero().sub_ct(self.value, F::MODULUS) }
+    }
+}
+
+impl<F: field::MontgomeryField> Mul for ConstantTimeElement<F> where F::Value: ConstantTimeArith {
+    type Output = Self;
+    fn mul(self, other: Self) -> Self::Output {
+        ConstantTimeElement { value: self.value.mul_reduce_ct(other.value, F::MODULUS, F::INV) }
+    }
+}
+
+impl<F: field::MontgomeryField> Mul<u32> for ConstantTimeElement<F> where F::Value: ConstantTimeArith {
+    type Output = Self;
+    fn mul(self, other: u32) -> Self {
+        // double-and-add, the small factor is public
+        (0..32).rev().fold(Self::zero(), |acc, i| {
+            let acc = acc + acc;
+            if (other >> i) & 1 == 1 { acc + self } else { acc }
+        })
+    }
+}
+
+impl<F: field::MontgomeryField> Div for ConstantTimeElement<F> where F::Value: ConstantTimeArith {
+    type Output = Self;
+    fn div(self, other: Self) -> Self::Output {
+        // only whether the divisor is zero leaks
+        assert!(!bool::from(other.ct_is_zero()), "division by zero");
+        // (b * R)^-1 = b^-1 * R^-1, brought back to b^-1 * R with two multiplications by R^2
+        let r2 = Self::r2();
+        let inverse = other.value.inv_ct(F::MODULUS)
+            .mul_reduce_ct(r2, F::MODULUS, F::INV)
+            .mul_reduce_ct(r2, F::MODULUS, F::INV);
+
+        ConstantTimeElement { value: self.value.mul_reduce_ct(inverse, F::MODULUS, F::INV) }
+    }
+}
+
+impl<F: field::MontgomeryField<Value=V>, V: ConstantTimeArith + Scalar> From<V> for ConstantTimeElement<F> {
+    /// New element from value, values not less than MODULUS are reduced in variable time
+    fn from(val: V) -> Self {
+        ConstantTimeElement { value: (val % F::MODULUS).mul_reduce_ct(Self::r2(), F::MODULUS, F::INV) }
+    }
+}
+
+impl<F: field::MontgomeryField> ConditionallySelectable for ConstantTimeElement<F> where F::Value: ConstantTimeArith {
+    fn conditional_select(a: &Self, b: &Self, choice: Choice) -> Self {
+        ConstantTimeElement { value: F::Value::conditional_select(&a.value, &b.value, choice) }
+    }
+}
+
+impl<F: field::MontgomeryField> ConstantTimeEq for ConstantTimeElement<F> where F::Value: ConstantTimeArith {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.value.ct_eq(&other.value)
+    }
+}
+
+impl<F: field::MontgomeryField> FieldValue for ConstantTimeElement<F> where F::Value: ConstantTimeArith {
+    type Value = F::Value;
+
+    /// Multiplication identity
+    fn one() -> Self {
+        ConstantTimeElement { value: F::R }
+    }
+
+    /// Additive identity
+    fn zero() -> Self {
+        ConstantTimeElement { value: F::Value::zero() }
+    }
+
+    fn characteristic() -> F::Value {
+        F::MODULUS
+    }
+
+    fn sqrt(self) -> Option<Self> {
+        sqrt::sqrt(self, Self::one(), F::MODULUS)
+    }
+
+    fn legendre(self) -> i8 {
+        sqrt::legendre(self, Self::one(), F::MODULUS)
+    }
+
+    fn pow_with_window(self, other: F::Value, width: usize) -> Self {
+        power::pow_fixed_ct(self, other, Self::one(), width)
+    }
+
+    #[cfg(feature = "rand")]
+    fn random<R: ::rand::RngCore + ?Sized>(rng: &mut R) -> Self {
+        // uniform in montgomery form as well, x -> x * R is a bijection
+        ConstantTimeElement { value: F::Value::random_below(rng, F::MODULUS) }
+    }
+}
+
+impl_field_ops!(impl<F: field::MontgomeryField> ConstantTimeElement<F> where F::Value: ConstantTimeArith);
+
+#[cfg(test)]
+mod tests {
+
+    use {ConstantTimeElement, MontgomeryElement, FieldElement, FieldValue};
+    use ct::{Choice, ConditionallySelectable, ConstantTimeEq};
+    use test::Mod1125899839733759Field;
+
+    use quickcheck::TestResult;
+
+    type Element = ConstantTimeElement<Mod1125899839733759Field>;
+
+    #[test]
+    fn smoky() {
+        let elem1: Element = 6.into();
+        let elem2: Element = 16.into();
+
+        assert_eq!((elem1 + elem2).into_element(), 22.into());
+        assert_eq!((elem1 - elem2).into_element(), FieldElement::from(6) - FieldElement::from(16));
+        assert_eq!(elem1 * elem2, 96.into());
+        assert_eq!(elem2 * 7, 112.into());
+        assert_eq!((elem2 / elem1) * elem1, elem2);
+        assert_eq!(Element::zero().invert(), None);
+        assert_eq!(elem2.checked_div(elem1), Some(elem2 / elem1));
+        assert_eq!(format!("{}", elem2.pow(2)), "256");
+
+        assert!(bool::from(Element::zero().ct_is_zero()));
+        assert!(!bool::from(elem1.ct_is_zero()));
+        assert_eq!(Element::conditional_select(&elem1, &elem2, Choice::from(1)), elem2);
+    }
+
+    quickcheck! {
+        fn equals_montgomery(x: u64, y: u64, z: u32) -> TestResult {
+            let (x_c, y_c): (Element, Element) = (x.into(), y.into());
+            let (x_m, y_m): (MontgomeryElement<Mod1125899839733759Field>, MontgomeryElement<Mod1125899839733759Field>) =
+                (x.into(), y.into());
+
+            if y_m == MontgomeryElement::zero() {
+                return TestResult::discard();
+            }
+
+            TestResult::from_bool(
+                x_c.into_montgomery() == x_m &&
+                    Element::from_montgomery(y_m) == y_c &&
+                    (x_c + y_c).into_montgomery() == x_m + y_m &&
+                    (x_c - y_c).into_montgomery() == x_m - y_m &&
+                    (-x_c).into_montgomery() == -x_m &&
+                    (x_c * y_c).into_montgomery() == x_m * y_m &&
+                    (x_c * z).into_montgomery() == x_m * z &&
+                    (x_c / y_c).into_montgomery() == x_m / y_m &&
+                    x_c.pow(y).into_montgomery() == x_m.pow(y) &&
+                    bool::from(x_c.ct_eq(&x_c)) &&
+                    bool::from(x_c.ct_eq(&y_c)) == (x_c == y_c)
+            )
+        }
+    }
+}
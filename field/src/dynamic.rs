@@ -0,0 +1,407 @@
+//! Prime fields with the modulus known at runtime
+
+use std::{error, fmt};
+use std::ops::{Add, Mul, Neg, Sub, Div, AddAssign, SubAssign, MulAssign, DivAssign};
+use std::rc::Rc;
+
+use arith::{Scalar, ModMul};
+use {sqrt, power};
+
+/// Modulus which cannot define a runtime field
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ModulusError {
+    /// Modulus is 0 or 1
+    TooSmall,
+    /// Modulus is even, montgomery reduction needs an odd one
+    Even,
+}
+
+impl fmt::Display for ModulusError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ModulusError::TooSmall => write!(f, "field modulus should be greater than 1"),
+            ModulusError::Even => write!(f, "field modulus should be odd"),
+        }
+    }
+}
+
+impl error::Error for ModulusError {}
+
+/// Prime field context: modulus and derived montgomery constants
+#[derive(Clone, Debug, PartialEq)]
+pub struct DynamicField<V: Scalar> {
+    modulus: V,
+    r: V,
+    r_inverse: V,
+    inv: u64,
+}
+
+impl<V: Scalar> DynamicField<V> {
+    /// New field context for the odd prime modulus
+    ///
+    /// Moduli below 2 and even ones are rejected, primality is not checked.
+    pub fn new(modulus: V) -> Result<Rc<Self>, ModulusError> {
+        if modulus == V::zero() || modulus == V::one() {
+            return Err(ModulusError::TooSmall);
+        }
+        if !modulus.bit(0) {
+            return Err(ModulusError::Even);
+        }
+
+        // R = 2^max_bits mod p
+        let mut r = V::one() % modulus;
+        for _ in 0..V::max_bits() {
+            r = r.add(r, modulus);
+        }
+
+        // -p^-1 mod 2^64 by Newton iteration on the lowest word
+        let low = (0..64).fold(0u64, |acc, i| acc | ((modulus.bit(i) as u64) << i));
+        let mut inv = 1u64;
+        for _ in 0..6 {
+            inv = inv.wrapping_mul(2u64.wrapping_sub(low.wrapping_mul(inv)));
+        }
+
+        Ok(Rc::new(DynamicField {
+            modulus,
+            r,
+            r_inverse: r.inv(modulus),
+            inv: inv.wrapping_neg(),
+        }))
+    }
+
+    /// Field modulus
+    pub fn modulus(&self) -> V {
+        self.modulus
+    }
+
+    /// Montgomery coefficient R = 2^max_bits mod MODULUS
+    pub fn r(&self) -> V {
+        self.r
+    }
+
+    /// Multiplicative inverse of R modulus MODULUS
+    pub fn r_inverse(&self) -> V {
+        self.r_inverse
+    }
+
+    /// Montgomery reduction constant (-MODULUS^-1 mod 2^64)
+    pub fn inv(&self) -> u64 {
+        self.inv
+    }
+}
+
+/// Element of the runtime field context, in montgomery representation
+///
+/// Arithmetic on elements of different fields panics.
+///
+/// The element does not implement `FieldValue`: its identities and characteristic depend on the
+/// context, while the trait provides them without a value at hand. Generic code over `FieldValue`,
+/// such as `batch_invert`, polynomials or curves, still needs a compile-time field.
+#[derive(Clone, Debug)]
+pub struct DynamicElement<V: Scalar> {
+    value: V,
+    field: Rc<DynamicField<V>>,
+}
+
+impl<V: Scalar> DynamicElement<V> {
+    /// New field element from regular form
+    pub fn new(value: V, field: &Rc<DynamicField<V>>) -> Self {
+        DynamicElement {
+            value: (value % field.modulus).mul(field.r, field.modulus),
+            field: field.clone(),
+        }
+    }
+
+    /// Multiplication identity of the field
+    pub fn one(field: &Rc<DynamicField<V>>) -> Self {
+        DynamicElement { value: field.r, field: field.clone() }
+    }
+
+    /// Additive identity of the field
+    pub fn zero(field: &Rc<DynamicField<V>>) -> Self {
+        DynamicElement { value: V::zero(), field: field.clone() }
+    }
+
+    /// Uniformly distributed random element of the field
+    #[cfg(feature = "rand")]
+    pub fn random<R: ::rand::RngCore + ?Sized>(rng: &mut R, field: &Rc<DynamicField<V>>) -> Self {
+        DynamicElement { value: V::random_below(rng, field.modulus), field: field.clone() }
+    }
+
+    /// Field context of the element
+    pub fn field(&self) -> &Rc<DynamicField<V>> {
+        &self.field
+    }
+
+    /// Test if both elements belong to the same field
+    pub fn same_field(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.field, &other.field) || self.field == other.field
+    }
+
+    /// Deconstruct and return value in regular form
+    pub fn into_value(self) -> V {
+        ModMul::<V>::mul(self.value, self.field.r_inverse, self.field.modulus)
+    }
+
+    fn with_value(&self, value: V) -> Self {
+        DynamicElement { value, field: self.field.clone() }
+    }
+
+    fn check_field(&self, other: &Self) {
+        assert!(
+            self.same_field(other),
+            "arithmetic on elements of different fields: {:?} and {:?}", self.field.modulus, other.field.modulus,
+        );
+    }
+
+    /// Squared field element
+    pub fn squared(self) -> Self {
+        self.clone() * self
+    }
+
+    /// self^other by fixed-window exponentiation
+    pub fn pow(self, other: V) -> Self {
+        let one = Self::one(&self.field);
+        power::pow_fixed(self, other, one, power::DEFAULT_WINDOW)
+    }
+
+    /// self^other by sliding-window exponentiation, running time depends on the exponent
+    pub fn pow_vartime(self, other: V) -> Self {
+        let one = Self::one(&self.field);
+        power::pow_sliding(self, other, one, power::DEFAULT_WINDOW)
+    }
+
+    /// Multiplicative inverse, zero is mapped to zero
+    pub fn inverse(self) -> Self {
+        // (a * R)^-1 * R * R = a^-1 * R
+        let value = ModMul::<V>::mul(self.value.inv(self.field.modulus), self.field.r, self.field.modulus);
+        self.with_value(ModMul::<V>::mul(value, self.field.r, self.field.modulus))
+    }
+
+    /// Multiplicative inverse, `None` for zero
+    pub fn invert(self) -> Option<Self> {
+        if self.value == V::zero() { None } else { Some(self.inverse()) }
+    }
+
+    /// self / other, `None` when other is zero
+    pub fn checked_div(self, other: Self) -> Option<Self> {
+        self.check_field(&other);
+        other.invert().map(|inverse| self * inverse)
+    }
+
+    /// Square root, if the element is a quadratic residue
+    pub fn sqrt(self) -> Option<Self> {
+        let (one, modulus) = (Self::one(&self.field), self.field.modulus);
+        sqrt::sqrt(self, one, modulus)
+    }
+
+    /// Legendre symbol of the element: 1 for non-zero squares, -1 for non-squares and 0 for zero
+    pub fn legendre(self) -> i8 {
+        let (one, modulus) = (Self::one(&self.field), self.field.modulus);
+        sqrt::legendre(self, one, modulus)
+    }
+
+    /// Test if the element is a quadratic residue (zero included)
+    pub fn is_square(self) -> bool {
+        self.legendre() >= 0
+    }
+}
+
+impl<V: Scalar> PartialEq for DynamicElement<V> {
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value && self.same_field(other)
+    }
+}
+
+impl<V: Scalar> Add for DynamicElement<V> {
+    type Output = Self;
+    fn add(self, other: Self) -> Self {
+        self.check_field(&other);
+        self.with_value(self.value.add(other.value, self.field.modulus))
+    }
+}
+
+impl<V: Scalar> Sub for DynamicElement<V> {
+    type Output = Self;
+    fn sub(self, other: Self) -> Self {
+        self.check_field(&other);
+        self.with_value(self.value.add(other.value.neg(self.field.modulus), self.field.modulus))
+    }
+}
+
+impl<V: Scalar> Neg for DynamicElement<V> {
+    type Output = Self;
+    fn neg(self) -> Self {
+        self.with_value(self.value.neg(self.field.modulus))
+    }
+}
+
+impl<V: Scalar> Mul for DynamicElement<V> {
+    type Output = Self;
+    fn mul(self, other: Self) -> Self {
+        self.check_field(&other);
+        let field = &self.field;
+        self.with_value(self.value.mul_reduce(other.value, field.modulus, field.r_inverse, field.inv))
+    }
+}
+
+impl<V: Scalar> Mul<u32> for DynamicElement<V> {
+    type Output = Self;
+    fn mul(self, other: u32) -> Self {
+        self.with_value(self.value.mul(other, self.field.modulus))
+    }
+}
+
+impl<V: Scalar> Div for DynamicElement<V> {
+    type Output = Self;
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn div(self, other: Self) -> Self {
+        self.check_field(&other);
+        assert!(other.value != V::zero(), "division by zero");
+        self * other.inverse()
+    }
+}
+
+macro_rules! impl_dynamic_ops {
+    ($op: ident, $method: ident, $op_assign: ident, $method_assign: ident) => {
+        impl<V: Scalar> $op_assign for DynamicElement<V> {
+            fn $method_assign(&mut self, other: Self) {
+                *self = $op::$method(self.clone(), other);
+            }
+        }
+
+        impl<'a, V: Scalar> $op_assign<&'a DynamicElement<V>> for DynamicElement<V> {
+            fn $method_assign(&mut self, other: &'a Self) {
+                *self = $op::$method(self.clone(), other.clone());
+            }
+        }
+
+        impl<'a, V: Scalar> $op<&'a DynamicElement<V>> for DynamicElement<V> {
+            type Output = Self;
+            fn $method(self, other: &'a Self) -> Self {
+                $op::$method(self, other.clone())
+            }
+        }
+
+        impl<'a, 'b, V: Scalar> $op<&'b DynamicElement<V>> for &'a DynamicElement<V> {
+            type Output = DynamicElement<V>;
+            fn $method(self, other: &'b DynamicElement<V>) -> DynamicElement<V> {
+                $op::$method(self.clone(), other.clone())
+            }
+        }
+    }
+}
+
+// no Sum and Product: the identities need the field context
+impl_dynamic_ops!(Add, add, AddAssign, add_assign);
+impl_dynamic_ops!(Sub, sub, SubAssign, sub_assign);
+impl_dynamic_ops!(Mul, mul, MulAssign, mul_assign);
+impl_dynamic_ops!(Div, div, DivAssign, div_assign);
+
+#[cfg(test)]
+mod tests {
+
+    use super::{DynamicField, DynamicElement, ModulusError};
+    use {MontgomeryField, FieldElement, FieldValue};
+    use test::{Mod19Field, Mod1125899839733759Field};
+
+    #[test]
+    fn constants() {
+        let field = DynamicField::new(1125899839733759u64).unwrap();
+
+        assert_eq!(field.r(), Mod1125899839733759Field::R);
+        assert_eq!(field.r_inverse(), Mod1125899839733759Field::R_INVERSE);
+        assert_eq!(field.inv(), Mod1125899839733759Field::INV);
+    }
+
+    #[test]
+    fn invalid_modulus() {
+        assert_eq!(DynamicField::new(0u64), Err(ModulusError::TooSmall));
+        assert_eq!(DynamicField::new(1u64), Err(ModulusError::TooSmall));
+        assert_eq!(DynamicField::new(20u64), Err(ModulusError::Even));
+    }
+
+    #[test]
+    fn smoky() {
+        let field = DynamicField::new(19u64).unwrap();
+        let elem = |v: u64| DynamicElement::new(v, &field);
+
+        let elem1 = elem(6);
+        let elem2 = elem(16);
+
+        assert_eq!(elem1.clone() + elem2.clone(), elem(3));
+        assert_eq!(elem1.clone() - elem2.clone(), elem(9));
+        assert_eq!(-elem1.clone(), elem(13));
+        assert_eq!(elem1.clone() * elem2.clone(), elem(1));
+        assert_eq!(elem2.clone() / elem1.clone(), elem(9));
+        assert_eq!(elem2.clone().checked_div(elem1.clone()), Some(elem(9)));
+        assert_eq!(elem1.clone().checked_div(elem(0)), None);
+        assert_eq!(elem(0).invert(), None);
+        assert_eq!(elem1.clone() * 2, elem(12));
+        assert_eq!(elem1.clone().pow(20), elem(17));
+        assert_eq!(elem2.clone().into_value(), 16);
+
+        let mut acc = elem1.clone();
+        acc += &elem2;
+        acc *= elem(2);
+        assert_eq!(acc, elem(6));
+        assert_eq!(&elem1 - &elem2, elem(9));
+
+        assert_eq!(elem(5).sqrt().unwrap().squared(), elem(5));
+        assert_eq!(elem(2).sqrt(), None);
+        assert_eq!(elem(2).legendre(), FieldElement::<Mod19Field>::from(2).legendre());
+    }
+
+    #[cfg(feature = "rand")]
+    #[test]
+    fn random() {
+        use rand::{SeedableRng, rngs::StdRng};
+
+        let mut rng = StdRng::seed_from_u64(19);
+        let field = DynamicField::new(19u64).unwrap();
+
+        let mut counts = [0usize; 19];
+        for _ in 0..19000 {
+            counts[DynamicElement::random(&mut rng, &field).into_value() as usize] += 1;
+        }
+
+        assert!(counts.iter().all(|&c| c > 850 && c < 1150), "{:?}", counts);
+    }
+
+    #[test]
+    fn same_modulus_different_contexts() {
+        let field1 = DynamicField::new(19u64).unwrap();
+        let field2 = DynamicField::new(19u64).unwrap();
+
+        assert_eq!(DynamicElement::new(6, &field1) + DynamicElement::new(16, &field2), DynamicElement::new(3, &field1));
+    }
+
+    #[test]
+    #[should_panic(expected = "different fields")]
+    fn mixing_fields() {
+        let field1 = DynamicField::new(19u64).unwrap();
+        let field2 = DynamicField::new(23u64).unwrap();
+
+        let _ = DynamicElement::new(6, &field1) * DynamicElement::new(16, &field2);
+    }
+
+    quickcheck! {
+        fn equals_static_field(x: u64, y: u64) -> bool {
+            let field = DynamicField::new(1125899839733759u64).unwrap();
+            let (x_d, y_d) = (DynamicElement::new(x, &field), DynamicElement::new(y, &field));
+            let (x_s, y_s) = (FieldElement::<Mod1125899839733759Field>::from(x), FieldElement::from(y));
+
+            (x_d.clone() * y_d.clone()).into_value() == (x_s * y_s).into_value() &&
+                (x_d.clone() + y_d.clone()).into_value() == (x_s + y_s).into_value() &&
+                (x_d.clone() - y_d.clone()).into_value() == (x_s - y_s).into_value() &&
+                (y_s == FieldValue::zero() || (x_d / y_d).into_value() == (x_s / y_s).into_value())
+        }
+
+        fn sqrt_of_square(x: u64) -> bool {
+            let field = DynamicField::new(998244353u64).unwrap();
+            let x_d = DynamicElement::new(x, &field);
+            let root = x_d.clone().squared().sqrt().unwrap();
+            root == x_d || root == -x_d
+        }
+    }
+}
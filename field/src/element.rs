@@ -1,9 +1,12 @@
 //! Abstract field element implementation
 
+use std::fmt;
 use std::ops::{Add, Mul, Neg, Sub, Div};
+use std::str::FromStr;
 
-use field;
+use {field, sqrt, encoding, text};
 use arith::{self, Scalar, ModAdd, ModMul, ModNeg, ModInv};
+use ct::{Choice, ConditionallySelectable, ConstantTimeEq};
 
 /// Field element on the field F with value V
 #[repr(C)]
@@ -18,6 +21,26 @@ impl<F: field::Field> FieldElement<F> {
     pub fn into_value(self) -> F::Value {
         self.value
     }
+
+    /// Fixed-width big-endian bytes of the value
+    pub fn to_bytes_be(&self) -> Vec<u8> {
+        encoding::encode(self.value, false)
+    }
+
+    /// Fixed-width little-endian bytes of the value
+    pub fn to_bytes_le(&self) -> Vec<u8> {
+        encoding::encode(self.value, true)
+    }
+
+    /// New element from fixed-width big-endian bytes, values not less than MODULUS are rejected
+    pub fn from_bytes_be(bytes: &[u8]) -> Result<Self, encoding::DecodeError> {
+        encoding::decode(bytes, F::MODULUS, false).map(|value| FieldElement { value })
+    }
+
+    /// New element from fixed-width little-endian bytes, values not less than MODULUS are rejected
+    pub fn from_bytes_le(bytes: &[u8]) -> Result<Self, encoding::DecodeError> {
+        encoding::decode(bytes, F::MODULUS, true).map(|value| FieldElement { value })
+    }
 }
 
 impl<F: field::Field> Add for FieldElement<F> {
@@ -58,6 +81,7 @@ impl<F: field::Field> Mul<u32> for FieldElement<F> {
 impl<F: field::Field> Div for FieldElement<F> {
     type Output = Self;
     fn div(self, other: Self) -> Self::Output {
+        assert!(other.value != F::Value::zero(), "division by zero");
         ModMul::<F::Value>::mul(self.value, other.value.inv(F::MODULUS), F::MODULUS).into()
     }
 }
@@ -71,6 +95,27 @@ impl<F: field::Field<Value=V>, V: arith::Scalar> From<V> for FieldElement<F>
     }
 }
 
+impl<F: field::Field> FromStr for FieldElement<F> {
+    type Err = text::ParseError;
+
+    /// Parse decimal or `0x`-prefixed hex value, values not less than MODULUS are rejected
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        text::parse_canonical(s, F::MODULUS).map(|value| FieldElement { value })
+    }
+}
+
+impl<F: field::Field> fmt::Display for FieldElement<F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.value.fmt_decimal(f)
+    }
+}
+
+impl<F: field::Field> fmt::LowerHex for FieldElement<F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.value.fmt_lower_hex(f)
+    }
+}
+
 impl<F: field::Field> field::FieldValue for FieldElement<F> {
     type Value = F::Value;
 
@@ -87,8 +132,39 @@ impl<F: field::Field> field::FieldValue for FieldElement<F> {
             value: F::Value::zero(),
         }
     }
+
+    fn characteristic() -> F::Value {
+        F::MODULUS
+    }
+
+    fn sqrt(self) -> Option<Self> {
+        sqrt::sqrt(self, Self::one(), F::MODULUS)
+    }
+
+    fn legendre(self) -> i8 {
+        sqrt::legendre(self, Self::one(), F::MODULUS)
+    }
+
+    #[cfg(feature = "rand")]
+    fn random<R: ::rand::RngCore + ?Sized>(rng: &mut R) -> Self {
+        FieldElement { value: F::Value::random_below(rng, F::MODULUS) }
+    }
 }
 
+impl<F: field::Field> ConditionallySelectable for FieldElement<F> where F::Value: ConditionallySelectable {
+    fn conditional_select(a: &Self, b: &Self, choice: Choice) -> Self {
+        FieldElement { value: F::Value::conditional_select(&a.value, &b.value, choice) }
+    }
+}
+
+impl<F: field::Field> ConstantTimeEq for FieldElement<F> where F::Value: ConstantTimeEq {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.value.ct_eq(&other.value)
+    }
+}
+
+impl_field_ops!(impl<F: field::Field> FieldElement<F>);
+
 #[cfg(test)]
 mod tests {
 
@@ -121,12 +197,150 @@ mod tests {
         assert_eq!(elem2.pow(10), 16.into());
      }
 
+    #[test]
+    fn add_overflow() {
+        use arith::ModAdd;
+
+        // 2^64 - 59 is prime, the sum of two values close to it does not fit u64
+        let p = u64::MAX - 58;
+        assert_eq!(ModAdd::add(p - 1, p - 1, p), p - 2);
+        assert_eq!(ModAdd::add(p - 1, 1, p), 0);
+    }
+
+    #[test]
+    fn fallible_division() {
+        let elem: FieldElement<Mod19Field> = 6.into();
+
+        assert_eq!(elem.invert(), Some(16.into()));
+        assert_eq!(FieldElement::<Mod19Field>::zero().invert(), None);
+        assert_eq!(elem.checked_div(16.into()), Some(17.into()));
+        assert_eq!(elem.checked_div(FieldElement::zero()), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "division by zero")]
+    fn division_by_zero() {
+        let _ = FieldElement::<Mod19Field>::one() / FieldElement::zero();
+    }
+
+    #[test]
+    fn bytes() {
+        use DecodeError;
+
+        let elem: FieldElement<Mod19Field> = 18.into();
+        assert_eq!(elem.to_bytes_be(), vec![0, 0, 0, 0, 0, 0, 0, 18]);
+        assert_eq!(elem.to_bytes_le(), vec![18, 0, 0, 0, 0, 0, 0, 0]);
+
+        assert_eq!(FieldElement::<Mod19Field>::from_bytes_be(&[0, 0, 0, 0, 0, 0, 0, 18]), Ok(elem));
+        assert_eq!(FieldElement::<Mod19Field>::from_bytes_le(&[18, 0, 0, 0, 0, 0, 0, 0]), Ok(elem));
+
+        assert_eq!(
+            FieldElement::<Mod19Field>::from_bytes_be(&[0, 0, 0, 0, 0, 0, 0, 19]),
+            Err(DecodeError::NotCanonical)
+        );
+        assert_eq!(
+            FieldElement::<Mod19Field>::from_bytes_le(&[18, 0, 0, 0, 0, 0, 0, 1]),
+            Err(DecodeError::NotCanonical)
+        );
+        assert_eq!(
+            FieldElement::<Mod19Field>::from_bytes_be(&[18]),
+            Err(DecodeError::InvalidLength { expected: 8, actual: 1 })
+        );
+    }
+
+    #[test]
+    fn strings() {
+        use ParseError;
+
+        let elem: FieldElement<Mod19Field> = 18.into();
+        assert_eq!("18".parse(), Ok(elem));
+        assert_eq!("0x12".parse(), Ok(elem));
+        assert_eq!(format!("{}", elem), "18");
+        assert_eq!(format!("{:#x}", elem), "0x12");
+
+        assert_eq!("19".parse::<FieldElement<Mod19Field>>(), Err(ParseError::NotCanonical));
+        assert_eq!("1 9".parse::<FieldElement<Mod19Field>>(), Err(ParseError::InvalidDigit));
+        assert_eq!("0x10000000000000000".parse::<FieldElement<Mod19Field>>(), Err(ParseError::Overflow));
+        assert_eq!("".parse::<FieldElement<Mod19Field>>(), Err(ParseError::Empty));
+    }
+
+    #[cfg(feature = "rand")]
+    #[test]
+    fn random_is_uniform() {
+        use rand::{SeedableRng, rngs::StdRng};
+
+        let mut rng = StdRng::seed_from_u64(19);
+        let mut counts = [0usize; 19];
+        for _ in 0..19000 {
+            counts[FieldElement::<Mod19Field>::random(&mut rng).into_value() as usize] += 1;
+        }
+
+        // ~1000 expected in each bucket, standard deviation ~31
+        assert!(counts.iter().all(|&c| c > 850 && c < 1150), "{:?}", counts);
+
+        for _ in 0..1000 {
+            assert!(FieldElement::<Mod19Field>::random_nonzero(&mut rng) != FieldElement::zero());
+            assert!(FieldElement::<Mod1125899839733759Field>::random(&mut rng).into_value() < 1125899839733759);
+        }
+    }
+
     fn field1_elem<T: Into<FieldElement<Mod1125899839733759Field>>>(v: T) -> FieldElement<Mod1125899839733759Field>
     {
         v.into()
     }
 
      quickcheck! {
+        fn bytes_roundtrip(x: u64) -> bool {
+            let x_e = field1_elem(x);
+
+            FieldElement::from_bytes_be(&x_e.to_bytes_be()) == Ok(x_e) &&
+                FieldElement::from_bytes_le(&x_e.to_bytes_le()) == Ok(x_e) &&
+                (x < 1125899839733759) == FieldElement::<Mod1125899839733759Field>::from_bytes_be(&x.to_be_bytes()).is_ok()
+        }
+
+        fn strings_roundtrip(x: u64) -> bool {
+            let x_e = field1_elem(x);
+
+            format!("{}", x_e).parse() == Ok(x_e) &&
+                format!("{:#x}", x_e).parse() == Ok(x_e) &&
+                format!("{}", x_e) == format!("{}", x % 1125899839733759)
+        }
+
+        fn pow_variants_agree(x: u64, e: u64, width: u8) -> bool {
+            let (x_e, width) = (field1_elem(x), 1 + width as usize % 8);
+            let expected = (0..64).rev().fold(FieldElement::one(), |acc, i| {
+                if e & (1 << i) != 0 { acc * acc * x_e } else { acc * acc }
+            });
+
+            x_e.pow(e) == expected &&
+                x_e.pow_with_window(e, width) == expected &&
+                x_e.pow_vartime(e) == expected &&
+                x_e.pow_vartime_with_window(e, width) == expected
+        }
+
+        // the reference forms are the point here
+        #[allow(clippy::op_ref)]
+        fn assign_reference_and_iterator_ops_agree(x: u64, y: u64, zs: Vec<u64>) -> bool {
+            let (x_e, y_e) = (field1_elem(x), field1_elem(y));
+            let zs: Vec<FieldElement<Mod1125899839733759Field>> = zs.into_iter().map(field1_elem).collect();
+
+            let mut assigned = x_e;
+            assigned += y_e;
+            assigned -= &x_e;
+            assigned *= y_e;
+            assigned /= &(y_e + FieldElement::one());
+
+            let expected = (x_e + y_e - x_e) * y_e / (y_e + FieldElement::one());
+
+            assigned == expected &&
+                &x_e + &y_e == x_e + y_e &&
+                x_e - &y_e == x_e - y_e &&
+                &x_e * y_e == x_e * y_e &&
+                -&x_e == -x_e &&
+                zs.iter().sum::<FieldElement<_>>() == zs.iter().fold(FieldElement::zero(), |acc, z| acc + *z) &&
+                zs.iter().copied().product::<FieldElement<_>>() == zs.iter().fold(FieldElement::one(), |acc, z| acc * *z)
+        }
+
         fn number_div_by_self_equals_one(x: u64) -> TestResult {
             if x % 1125899839733759 == 0 {
                 TestResult::discard()
@@ -0,0 +1,67 @@
+//! Canonical fixed-width byte encoding of field element values
+
+use std::{error, fmt};
+
+use arith::Scalar;
+
+/// Byte decoding error
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DecodeError {
+    /// Input is not exactly the encoding width
+    InvalidLength {
+        /// Encoding width of the value type, in bytes
+        expected: usize,
+        /// Length of the input
+        actual: usize,
+    },
+    /// Encoded value is not less than the field modulus
+    NotCanonical,
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            DecodeError::InvalidLength { expected, actual } =>
+                write!(f, "invalid encoding length: expected {} bytes, got {}", expected, actual),
+            DecodeError::NotCanonical => write!(f, "encoded value is not less than the field modulus"),
+        }
+    }
+}
+
+impl error::Error for DecodeError {}
+
+/// Encoding width of the value type, in bytes
+pub(crate) fn width<V: Scalar>() -> usize {
+    V::max_bits() / 8
+}
+
+/// Fixed-width encoding of the value
+pub(crate) fn encode<V: Scalar>(value: V, little_endian: bool) -> Vec<u8> {
+    let mut bytes = vec![0u8; width::<V>()];
+    value.write_be_bytes(&mut bytes);
+    if little_endian {
+        bytes.reverse();
+    }
+    bytes
+}
+
+/// Decode fixed-width value, rejecting values not less than the modulus
+pub(crate) fn decode<V: Scalar>(bytes: &[u8], modulus: V, little_endian: bool) -> Result<V, DecodeError> {
+    if bytes.len() != width::<V>() {
+        return Err(DecodeError::InvalidLength { expected: width::<V>(), actual: bytes.len() });
+    }
+
+    let value = if little_endian {
+        let mut be = bytes.to_vec();
+        be.reverse();
+        V::read_be_bytes(&be)
+    } else {
+        V::read_be_bytes(bytes)
+    };
+
+    if value % modulus != value {
+        return Err(DecodeError::NotCanonical);
+    }
+
+    Ok(value)
+}
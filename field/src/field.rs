@@ -1,7 +1,10 @@
 //! Abstract field
 
-use std::ops::{Add, Mul, Sub, Div, Neg};
+use std::iter::{Sum, Product};
+use std::ops::{Add, Mul, Sub, Div, Neg, AddAssign, SubAssign, MulAssign, DivAssign};
 use arith::Scalar;
+use ct::{Choice, ConstantTimeEq};
+use power;
 
 /// Abstract field description
 pub trait Field: Copy + Clone + PartialEq + ::std::fmt::Debug {
@@ -9,13 +12,40 @@ pub trait Field: Copy + Clone + PartialEq + ::std::fmt::Debug {
     type Value: Scalar;
     /// Field modulus
     const MODULUS: Self::Value;
-    /// Field montgomery coefficient (R)
+}
+
+/// Field with montgomery parameters, required by `MontgomeryElement`
+///
+/// Montgomery constants can be derived from the modulus limbs at compile time with
+/// [`montgomery_r`](fn.montgomery_r.html), [`montgomery_r_inverse`](fn.montgomery_r_inverse.html)
+/// and [`montgomery_inv`](fn.montgomery_inv.html); hand-written ones can be verified
+/// with `check_montgomery_constants!`.
+pub trait MontgomeryField: Field {
+    /// Field montgomery coefficient (R = 2^(64 * limbs) mod MODULUS)
     const R: Self::Value;
     /// Multiplicative inverse of R modulus MODULUS
     const R_INVERSE: Self::Value;
+    /// Montgomery reduction constant (-MODULUS^-1 mod 2^64)
+    const INV: u64;
+}
+
+/// Field with Barrett reduction constant, required by `BarrettElement`
+///
+/// The constant can be derived from the modulus limbs at compile time with
+/// [`barrett_mu`](fn.barrett_mu.html).
+pub trait BarrettField: Field {
+    /// Barrett constant without its implicit top bit (floor(2^(2k) / MODULUS) - 2^k, k is the bit length of MODULUS)
+    const MU: Self::Value;
 }
 
 /// Generalization of field element
+///
+/// Assignment, reference and iterator operators are derived from the by-value ones
+/// with [`impl_field_ops!`](macro.impl_field_ops.html). Operators with a reference on the
+/// left, such as `&a + &b`, are implemented by the macro as well, but cannot be required here.
+///
+/// Division by zero panics, [`invert`](#method.invert) and [`checked_div`](#method.checked_div)
+/// are the fallible alternatives.
 pub trait FieldValue:
     Sized +
     Clone +
@@ -27,7 +57,23 @@ pub trait FieldValue:
     Add<Output=Self> +
     Div<Output=Self> +
     Sub<Output=Self> +
-    Neg<Output=Self>
+    Neg<Output=Self> +
+    AddAssign +
+    SubAssign +
+    MulAssign +
+    DivAssign +
+    for<'a> Add<&'a Self, Output=Self> +
+    for<'a> Sub<&'a Self, Output=Self> +
+    for<'a> Mul<&'a Self, Output=Self> +
+    for<'a> Div<&'a Self, Output=Self> +
+    for<'a> AddAssign<&'a Self> +
+    for<'a> SubAssign<&'a Self> +
+    for<'a> MulAssign<&'a Self> +
+    for<'a> DivAssign<&'a Self> +
+    Sum +
+    Product +
+    for<'a> Sum<&'a Self> +
+    for<'a> Product<&'a Self>
 {
     /// Inner scalar value type of field element
     type Value: Scalar;
@@ -37,18 +83,93 @@ pub trait FieldValue:
         self * self
     }
 
-    /// self^other
+    /// self^other by fixed-window exponentiation with the default window width
     fn pow(self, other: Self::Value) -> Self {
-        let mut res = Self::one();
+        self.pow_with_window(other, power::DEFAULT_WINDOW)
+    }
+
+    /// self^other by fixed-window exponentiation, `width` is within 1..=8
+    ///
+    /// Performs the same sequence of operations for every exponent, at the cost of
+    /// `Value::max_bits()` squarings even for small ones.
+    fn pow_with_window(self, other: Self::Value, width: usize) -> Self {
+        power::pow_fixed(self, other, Self::one(), width)
+    }
+
+    /// self^other by sliding-window exponentiation with the default window width
+    fn pow_vartime(self, other: Self::Value) -> Self {
+        self.pow_vartime_with_window(other, power::DEFAULT_WINDOW)
+    }
+
+    /// self^other by sliding-window exponentiation, `width` is within 1..=8
+    ///
+    /// Skips leading zeros of the exponent, so the running time depends on it: meant
+    /// for public exponents, such as the ones of inversion by Fermat's little theorem.
+    fn pow_vartime_with_window(self, other: Self::Value, width: usize) -> Self {
+        power::pow_sliding(self, other, Self::one(), width)
+    }
+
+    /// Field characteristic p
+    fn characteristic() -> Self::Value;
+
+    /// Degree of the field over its prime subfield
+    fn degree() -> usize {
+        1
+    }
 
-        for i in other.bits() {
-            res = res.squared();
-            if i {
-                res = self * res;
+    /// Frobenius map x -> x^(p^power), p is the field characteristic
+    ///
+    /// Identity for prime fields.
+    fn frobenius_map(self, _power: usize) -> Self {
+        self
+    }
+
+    /// Square root, if the element is a quadratic residue
+    fn sqrt(self) -> Option<Self>;
+
+    /// Legendre symbol of the field element: 1 for non-zero squares, -1 for non-squares and 0 for zero
+    fn legendre(self) -> i8;
+
+    /// Test if the field element is a quadratic residue (zero included)
+    fn is_square(self) -> bool {
+        self.legendre() >= 0
+    }
+
+    /// Uniformly distributed random field element
+    ///
+    /// The default samples a value below the characteristic and maps it by double-and-add,
+    /// which only suits prime fields: extension fields should override it.
+    #[cfg(feature = "rand")]
+    fn random<R: ::rand::RngCore + ?Sized>(rng: &mut R) -> Self {
+        Self::Value::random_below(rng, Self::characteristic())
+            .bits()
+            .fold(Self::zero(), |acc, bit| if bit { acc + acc + Self::one() } else { acc + acc })
+    }
+
+    /// Uniformly distributed random non-zero field element
+    #[cfg(feature = "rand")]
+    fn random_nonzero<R: ::rand::RngCore + ?Sized>(rng: &mut R) -> Self {
+        loop {
+            let value = Self::random(rng);
+            if value != Self::zero() {
+                return value;
             }
         }
+    }
+
+    /// Multiplicative inverse, `None` for zero
+    fn invert(self) -> Option<Self> {
+        if self == Self::zero() { None } else { Some(Self::one() / self) }
+    }
+
+    /// self / other, `None` when other is zero
+    fn checked_div(self, other: Self) -> Option<Self> {
+        other.invert().map(|inverse| self * inverse)
+    }
 
-        res
+    /// Test if the field element is zero, in constant time
+    fn ct_is_zero(&self) -> Choice where Self: ConstantTimeEq {
+        self.ct_eq(&Self::zero())
     }
 
     /// Multiplication identity
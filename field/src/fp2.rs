@@ -0,0 +1,525 @@
+//! Quadratic extension field
+
+use std::marker::PhantomData;
+use std::ops::{Add, Mul, Neg, Sub, Div};
+use std::sync::Mutex;
+
+use field::FieldValue;
+use arith::{Scalar, ModMul};
+use ct::{Choice, ConditionallySelectable, ConstantTimeEq};
+
+/// Non-residue of the base field used to build an extension
+pub trait NonResidue<F: FieldValue>: Copy + Clone + PartialEq + ::std::fmt::Debug {
+    /// Non-residue value
+    fn non_residue() -> F;
+
+    /// Frobenius map coefficient N^((p^power - 1) / degree) of the extension of the given degree
+    ///
+    /// The default computes it with an exponentiation over the characteristic on every call,
+    /// non-residues of a concrete field should look it up in a static [`FrobeniusTable`].
+    fn frobenius_coefficient(degree: u32, power: usize) -> F {
+        frobenius_coefficient(Self::non_residue(), degree, power)
+    }
+}
+
+/// -1 as a non-residue (quadratic non-residue in prime fields with p = 3 mod 4)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MinusOne;
+
+impl<F: FieldValue> NonResidue<F> for MinusOne {
+    fn non_residue() -> F {
+        -F::one()
+    }
+
+    fn frobenius_coefficient(degree: u32, power: usize) -> F {
+        if degree != 2 {
+            return frobenius_coefficient(Self::non_residue(), degree, power);
+        }
+
+        // (-1)^((p^k - 1) / 2) = -1 iff p^k = 3 mod 4, that is p = 3 mod 4 and k is odd
+        if power % 2 == 1 && three_mod_four(F::characteristic()) {
+            -F::one()
+        } else {
+            F::one()
+        }
+    }
+}
+
+/// (p - 1) / divisor, divisor should divide p - 1
+///
+/// Computed as -(divisor^-1) mod p, which equals (p - 1) / divisor only when the division is exact.
+fn minus_one_div<V: Scalar>(p: V, divisor: u32) -> V {
+    ModMul::<V>::mul(V::one().neg(p), ModMul::<u32>::mul(V::one(), divisor, p).inv(p), p)
+}
+
+/// x^((p - 1) / divisor), p is the field characteristic
+pub(crate) fn pow_minus_one_div<F: FieldValue>(x: F, divisor: u32) -> F {
+    x.pow_vartime(minus_one_div(F::characteristic(), divisor))
+}
+
+/// Test if divisor divides p - 1
+fn divides_minus_one<V: Scalar>(p: V, divisor: u32) -> bool {
+    V::one().neg(p) % ModMul::<u32>::mul(V::one(), divisor, p) == V::zero()
+}
+
+/// Test if p = 3 mod 4
+fn three_mod_four<V: Scalar>(p: V) -> bool {
+    p % ModMul::<u32>::mul(V::one(), 4, p) == ModMul::<u32>::mul(V::one(), 3, p)
+}
+
+/// N^((p - 1) / degree), panics unless p = 1 mod degree
+fn frobenius_base<F: FieldValue>(non_residue: F, degree: u32) -> F {
+    let p = F::characteristic();
+    assert!(
+        divides_minus_one(p, degree),
+        "Frobenius map of the degree {} extension requires p = 1 mod {}, got p = {:?}",
+        degree, degree, p,
+    );
+
+    pow_minus_one_div(non_residue, degree)
+}
+
+/// Frobenius map coefficient N^((p^power - 1) / degree) of the extension of the given degree
+///
+/// Computed as the product of N^((p - 1) / degree) raised to p^i for i < power, hence requires
+/// p = 1 mod degree.
+pub(crate) fn frobenius_coefficient<F: FieldValue>(non_residue: F, degree: u32, power: usize) -> F {
+    let base = frobenius_base(non_residue, degree);
+
+    (0..power).fold(F::one(), |acc, i| acc * base.frobenius_map(i))
+}
+
+/// Frobenius map coefficients of every power below `count`, see `frobenius_coefficient`
+pub(crate) fn frobenius_coefficients<F: FieldValue>(non_residue: F, degree: u32, count: usize) -> Vec<F> {
+    let base = frobenius_base(non_residue, degree);
+
+    (0..count)
+        .scan(F::one(), |acc, i| {
+            let coefficient = *acc;
+            *acc = coefficient * base.frobenius_map(i);
+            Some(coefficient)
+        })
+        .collect()
+}
+
+/// Frobenius map coefficients of a non-residue, computed once per extension degree
+///
+/// Meant to be kept in a `static` by the [`NonResidue`] implementation of a concrete field:
+///
+/// ```
+/// # use ng_field::{Field, FieldElement, FieldValue, Fp2, Fp6, FrobeniusTable, MinusOne, NonResidue};
+/// #[derive(Clone, Copy, Debug, PartialEq)]
+/// struct Mod19;
+///
+/// impl Field for Mod19 {
+///     type Value = u64;
+///     const MODULUS: u64 = 19;
+/// }
+///
+/// type Fq2 = Fp2<FieldElement<Mod19>, MinusOne>;
+///
+/// /// 1 + u
+/// #[derive(Clone, Copy, Debug, PartialEq)]
+/// struct OnePlusU;
+///
+/// impl NonResidue<Fq2> for OnePlusU {
+///     fn non_residue() -> Fq2 {
+///         Fp2::new(1.into(), 1.into())
+///     }
+///
+///     fn frobenius_coefficient(degree: u32, power: usize) -> Fq2 {
+///         static TABLE: FrobeniusTable<Fq2> = FrobeniusTable::new();
+///         TABLE.get::<Self>(degree, power)
+///     }
+/// }
+///
+/// let a = Fp6::<Fq2, OnePlusU>::new(Fq2::one(), Fq2::one() * 2, Fq2::one() * 3);
+/// assert_eq!(a.frobenius_map(1), a.pow(19));
+/// ```
+pub struct FrobeniusTable<F> {
+    tables: Mutex<Vec<(u32, Vec<F>)>>,
+}
+
+impl<F: FieldValue> FrobeniusTable<F> {
+    /// New empty table
+    pub const fn new() -> Self {
+        FrobeniusTable { tables: Mutex::new(Vec::new()) }
+    }
+
+    /// Frobenius map coefficient N^((p^power - 1) / degree), see [`NonResidue::frobenius_coefficient`]
+    ///
+    /// Coefficients of all powers below the extension degree are computed on the first call for
+    /// the given degree.
+    pub fn get<N: NonResidue<F>>(&self, degree: u32, power: usize) -> F {
+        let count = degree as usize * F::degree();
+        let power = power % count;
+
+        if let Some(coefficient) = self.lookup(degree, power) {
+            return coefficient;
+        }
+
+        // not locked while computing, the base field may look up a table of its own
+        let coefficients = frobenius_coefficients(N::non_residue(), degree, count);
+        let coefficient = coefficients[power];
+
+        let mut tables = self.tables.lock().expect("Frobenius table is never poisoned");
+        if tables.iter().all(|&(d, _)| d != degree) {
+            tables.push((degree, coefficients));
+        }
+
+        coefficient
+    }
+
+    fn lookup(&self, degree: u32, power: usize) -> Option<F> {
+        let tables = self.tables.lock().expect("Frobenius table is never poisoned");
+        tables.iter().find(|&&(d, _)| d == degree).map(|(_, coefficients)| coefficients[power])
+    }
+}
+
+impl<F: FieldValue> Default for FrobeniusTable<F> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Element of the quadratic extension F[u] / (u^2 - N), N is a quadratic non-residue of F
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Fp2<F: FieldValue, N: NonResidue<F>> {
+    c0: F,
+    c1: F,
+    _non_residue: PhantomData<N>,
+}
+
+impl<F: FieldValue, N: NonResidue<F>> Fp2<F, N> {
+    /// New element c0 + c1 * u
+    pub fn new(c0: F, c1: F) -> Self {
+        Fp2 {
+            c0,
+            c1,
+            _non_residue: PhantomData,
+        }
+    }
+
+    /// Coefficient of 1
+    pub fn c0(&self) -> F {
+        self.c0
+    }
+
+    /// Coefficient of u
+    pub fn c1(&self) -> F {
+        self.c1
+    }
+
+    /// Deconstruct element into coefficients (c0, c1)
+    pub fn into_parts(self) -> (F, F) {
+        (self.c0, self.c1)
+    }
+
+    /// Conjugate element, c0 - c1 * u
+    pub fn conjugate(self) -> Self {
+        Self::new(self.c0, -self.c1)
+    }
+
+    /// Norm, self * conjugate = c0^2 - N * c1^2
+    pub fn norm(self) -> F {
+        self.c0.squared() - N::non_residue() * self.c1.squared()
+    }
+
+    /// Multiplicative inverse through the norm, zero is mapped to zero
+    pub fn inverse(self) -> Self {
+        self.invert().unwrap_or(self)
+    }
+
+    /// Multiply by the element of the base field
+    pub fn scale(self, other: F) -> Self {
+        Self::new(self.c0 * other, self.c1 * other)
+    }
+
+}
+
+impl<F: FieldValue, N: NonResidue<F>> From<F> for Fp2<F, N> {
+    fn from(c0: F) -> Self {
+        Self::new(c0, F::zero())
+    }
+}
+
+impl<F: FieldValue, N: NonResidue<F>> Add for Fp2<F, N> {
+    type Output = Self;
+    fn add(self, other: Self) -> Self {
+        Self::new(self.c0 + other.c0, self.c1 + other.c1)
+    }
+}
+
+impl<F: FieldValue, N: NonResidue<F>> Sub for Fp2<F, N> {
+    type Output = Self;
+    fn sub(self, other: Self) -> Self {
+        Self::new(self.c0 - other.c0, self.c1 - other.c1)
+    }
+}
+
+impl<F: FieldValue, N: NonResidue<F>> Neg for Fp2<F, N> {
+    type Output = Self;
+    fn neg(self) -> Self {
+        Self::new(-self.c0, -self.c1)
+    }
+}
+
+impl<F: FieldValue, N: NonResidue<F>> Mul for Fp2<F, N> {
+    type Output = Self;
+    fn mul(self, other: Self) -> Self {
+        // Karatsuba
+        let v0 = self.c0 * other.c0;
+        let v1 = self.c1 * other.c1;
+
+        Self::new(
+            v0 + N::non_residue() * v1,
+            (self.c0 + self.c1) * (other.c0 + other.c1) - v0 - v1,
+        )
+    }
+}
+
+impl<F: FieldValue, N: NonResidue<F>> Mul<u32> for Fp2<F, N> {
+    type Output = Self;
+    fn mul(self, other: u32) -> Self {
+        Self::new(self.c0 * other, self.c1 * other)
+    }
+}
+
+impl<F: FieldValue, N: NonResidue<F>> Div for Fp2<F, N> {
+    type Output = Self;
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn div(self, other: Self) -> Self {
+        self * other.invert().expect("division by zero")
+    }
+}
+
+impl<F: FieldValue, N: NonResidue<F>> FieldValue for Fp2<F, N> {
+    type Value = F::Value;
+
+    fn squared(self) -> Self {
+        // (c0 + c1*u)^2 = c0^2 + N*c1^2 + 2*c0*c1*u
+        let v = self.c0 * self.c1;
+
+        Self::new(
+            (self.c0 + self.c1) * (self.c0 + N::non_residue() * self.c1) - v - N::non_residue() * v,
+            v * 2,
+        )
+    }
+
+    fn characteristic() -> F::Value {
+        F::characteristic()
+    }
+
+    fn invert(self) -> Option<Self> {
+        self.norm().invert().map(|norm_inv| Self::new(self.c0 * norm_inv, -(self.c1 * norm_inv)))
+    }
+
+    fn degree() -> usize {
+        2 * F::degree()
+    }
+
+    fn frobenius_map(self, power: usize) -> Self {
+        // u^(p^k) = u * N^((p^k - 1) / 2)
+        let power = power % Self::degree();
+        Self::new(self.c0.frobenius_map(power), self.c1.frobenius_map(power) * N::frobenius_coefficient(2, power))
+    }
+
+    fn sqrt(self) -> Option<Self> {
+        if self.c1 == F::zero() {
+            // either c0 is a square in F, or c0 / N is and the root is pure imaginary
+            return match self.c0.sqrt() {
+                Some(root) => Some(Self::new(root, F::zero())),
+                None => (self.c0 / N::non_residue()).sqrt().map(|root| Self::new(F::zero(), root)),
+            };
+        }
+
+        // (x0 + x1*u)^2 = a0 + a1*u
+        //   x0^2 = (a0 +- sqrt(norm)) / 2, x1 = a1 / (2 * x0)
+        let alpha = self.norm().sqrt()?;
+        let two_inv = F::one() / (F::one() * 2);
+
+        let mut delta = (self.c0 + alpha) * two_inv;
+        if !delta.is_square() {
+            delta = (self.c0 - alpha) * two_inv;
+        }
+
+        let x0 = delta.sqrt()?;
+        let x1 = self.c1 / (x0 * 2);
+
+        Some(Self::new(x0, x1))
+    }
+
+    fn legendre(self) -> i8 {
+        // element is a square iff its norm is a square in the base field
+        self.norm().legendre()
+    }
+
+    #[cfg(feature = "rand")]
+    fn random<R: ::rand::RngCore + ?Sized>(rng: &mut R) -> Self {
+        Self::new(F::random(rng), F::random(rng))
+    }
+
+    fn one() -> Self {
+        Self::new(F::one(), F::zero())
+    }
+
+    fn zero() -> Self {
+        Self::new(F::zero(), F::zero())
+    }
+}
+
+impl<F: FieldValue + ConditionallySelectable, N: NonResidue<F>> ConditionallySelectable for Fp2<F, N> {
+    fn conditional_select(a: &Self, b: &Self, choice: Choice) -> Self {
+        Self::new(F::conditional_select(&a.c0, &b.c0, choice), F::conditional_select(&a.c1, &b.c1, choice))
+    }
+}
+
+impl<F: FieldValue + ConstantTimeEq, N: NonResidue<F>> ConstantTimeEq for Fp2<F, N> {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.c0.ct_eq(&other.c0) & self.c1.ct_eq(&other.c1)
+    }
+}
+
+impl_field_ops!(impl<F: FieldValue, N: NonResidue<F>> Fp2<F, N>);
+
+#[cfg(test)]
+mod tests {
+
+    use super::{Fp2, MinusOne};
+    use {FieldElement, MontgomeryElement, FieldValue};
+    use test::{Mod19Field, Mod1125899839733759Field};
+
+    type Fp2Mod19 = Fp2<FieldElement<Mod19Field>, MinusOne>;
+    type Fp2Big = Fp2<MontgomeryElement<Mod1125899839733759Field>, MinusOne>;
+
+    fn fp2_mod19(c0: u64, c1: u64) -> Fp2Mod19 {
+        Fp2::new(c0.into(), c1.into())
+    }
+
+    fn fp2_big(c0: u64, c1: u64) -> Fp2Big {
+        Fp2::new(c0.into(), c1.into())
+    }
+
+    #[test]
+    fn smoky() {
+        let a = fp2_mod19(3, 4);
+        let b = fp2_mod19(5, 7);
+
+        assert_eq!(a + b, fp2_mod19(8, 11));
+        assert_eq!(a - b, fp2_mod19(17, 16));
+
+        // (3 + 4u)(5 + 7u) = 15 - 28 + (21 + 20)u = -13 + 41u
+        assert_eq!(a * b, fp2_mod19(6, 3));
+        assert_eq!(a.squared(), a * a);
+
+        // norm(3 + 4u) = 9 + 16 = 25 = 6
+        assert_eq!(a.norm(), 6.into());
+        assert_eq!(a * a.conjugate(), a.norm().into());
+
+        assert_eq!(a * a.inverse(), Fp2::one());
+        assert_eq!(a / b * b, a);
+        assert_eq!(a.invert(), Some(a.inverse()));
+        assert_eq!((a - a).invert(), None);
+        assert_eq!(a.checked_div(Fp2::zero()), None);
+
+        // u^2 = -1
+        assert_eq!(fp2_mod19(0, 1).squared(), -Fp2::one());
+    }
+
+    /// Extension by -1 of any field with p = 3 mod 4
+    fn minus_one_extension<F: FieldValue>() {
+        let a: Fp2<F, MinusOne> = Fp2::new(F::one() * 3, F::one() * 4);
+        let b: Fp2<F, MinusOne> = Fp2::new(F::one() * 5, F::one() * 7);
+
+        assert_eq!(a * a.inverse(), Fp2::one());
+        assert_eq!(a / b * b, a);
+        assert_eq!(a.norm(), F::one() * 25);
+
+        let root = a.squared().sqrt().unwrap();
+        assert!(root == a || root == -a);
+    }
+
+    #[test]
+    fn minus_one() {
+        minus_one_extension::<FieldElement<Mod19Field>>();
+        minus_one_extension::<MontgomeryElement<Mod1125899839733759Field>>();
+    }
+
+    #[test]
+    fn frobenius() {
+        let a = fp2_mod19(3, 4);
+
+        assert_eq!(a.frobenius_map(1), a.pow(19));
+        assert_eq!(a.frobenius_map(2), a);
+        assert_eq!(a.frobenius_map(1), a.conjugate());
+    }
+
+    #[test]
+    fn minus_one_frobenius_coefficients() {
+        use fp2::frobenius_coefficient;
+        use test::Mod998244353Field;
+        use NonResidue;
+
+        type Fq = FieldElement<Mod19Field>;
+        type Fr = FieldElement<Mod998244353Field>;
+
+        // 19 = 3 mod 4 and 998244353 = 1 mod 4
+        for k in 0..4 {
+            let coefficient: Fq = MinusOne::frobenius_coefficient(2, k);
+            assert_eq!(coefficient, frobenius_coefficient(-Fq::one(), 2, k));
+
+            let coefficient: Fr = MinusOne::frobenius_coefficient(2, k);
+            assert_eq!(coefficient, frobenius_coefficient(-Fr::one(), 2, k));
+        }
+    }
+
+    #[test]
+    fn sqrt() {
+        // every element of the base field is a square in the extension
+        for x in 1..19 {
+            let a = fp2_mod19(x, 0);
+            assert_eq!(a.sqrt().unwrap().squared(), a);
+        }
+
+        let mut squares = 0;
+        for c0 in 0..19 {
+            for c1 in 0..19 {
+                let a = fp2_mod19(c0, c1);
+                match a.sqrt() {
+                    Some(root) => {
+                        assert_eq!(root.squared(), a);
+                        assert!(a.is_square());
+                        squares += 1;
+                    },
+                    None => assert!(!a.is_square()),
+                }
+            }
+        }
+
+        // zero and half of the multiplicative group
+        assert_eq!(squares, (19 * 19 - 1) / 2 + 1);
+    }
+
+    quickcheck! {
+        fn inverse_through_norm(c0: u64, c1: u64) -> bool {
+            let a = fp2_big(c0, c1);
+            a == Fp2::zero() || a * a.inverse() == Fp2::one()
+        }
+
+        fn mul_distributive(a0: u64, a1: u64, b0: u64, b1: u64, c0: u64, c1: u64) -> bool {
+            let (a, b, c) = (fp2_big(a0, a1), fp2_big(b0, b1), fp2_big(c0, c1));
+            a * (b + c) == a * b + a * c
+        }
+
+        fn sqrt_of_square(c0: u64, c1: u64) -> bool {
+            let a = fp2_big(c0, c1);
+            let root = a.squared().sqrt().unwrap();
+            root == a || root == -a
+        }
+
+        fn frobenius_is_pow(c0: u64, c1: u64) -> bool {
+            let a = fp2_big(c0, c1);
+            a.frobenius_map(1) == a.pow(1125899839733759)
+        }
+    }
+}
@@ -0,0 +1,408 @@
+//! Cubic extension field and the Fp2 -> Fp6 -> Fp12 tower
+
+use std::marker::PhantomData;
+use std::ops::{Add, Mul, Neg, Sub, Div};
+
+use field::FieldValue;
+use fp2::{Fp2, NonResidue, frobenius_coefficient, pow_minus_one_div};
+use ct::{Choice, ConditionallySelectable, ConstantTimeEq};
+
+/// Element of the cubic extension F[v] / (v^3 - N), N is neither a square nor a cube in F
+///
+/// Frobenius map (and everything built on it) requires p = 1 mod 3.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Fp6<F: FieldValue, N: NonResidue<F>> {
+    c0: F,
+    c1: F,
+    c2: F,
+    _non_residue: PhantomData<N>,
+}
+
+/// v, the cube root of the Fp6 non-residue, serving as the non-residue of Fp12 (w^2 = v)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct NonResidueCubeRoot;
+
+impl<F: FieldValue, N: NonResidue<F>> NonResidue<Fp6<F, N>> for NonResidueCubeRoot {
+    fn non_residue() -> Fp6<F, N> {
+        Fp6::new(F::zero(), F::one(), F::zero())
+    }
+
+    fn frobenius_coefficient(degree: u32, power: usize) -> Fp6<F, N> {
+        if degree != 2 {
+            return frobenius_coefficient(Self::non_residue(), degree, power);
+        }
+
+        // v^((p^k - 1) / 2) = N^((p^k - 1) / 6), p = 1 mod 3 makes p^k - 1 divisible by 6
+        Fp6::from(N::frobenius_coefficient(6, power))
+    }
+}
+
+/// Element of the quadratic extension over the cubic extension, Fp6[w] / (w^2 - v)
+pub type Fp12<F, N> = Fp2<Fp6<F, N>, NonResidueCubeRoot>;
+
+impl<F: FieldValue, N: NonResidue<F>> Fp6<F, N> {
+    /// New element c0 + c1 * v + c2 * v^2
+    pub fn new(c0: F, c1: F, c2: F) -> Self {
+        Fp6 {
+            c0,
+            c1,
+            c2,
+            _non_residue: PhantomData,
+        }
+    }
+
+    /// Coefficient of 1
+    pub fn c0(&self) -> F {
+        self.c0
+    }
+
+    /// Coefficient of v
+    pub fn c1(&self) -> F {
+        self.c1
+    }
+
+    /// Coefficient of v^2
+    pub fn c2(&self) -> F {
+        self.c2
+    }
+
+    /// Deconstruct element into coefficients (c0, c1, c2)
+    pub fn into_parts(self) -> (F, F, F) {
+        (self.c0, self.c1, self.c2)
+    }
+
+    /// Norm to the base field, product of all conjugates x * x^q * x^(q^2), q = |F|
+    pub fn norm(self) -> F {
+        let q = F::degree();
+        (self * self.frobenius_map(q) * self.frobenius_map(2 * q)).c0
+    }
+
+    /// Multiplicative inverse, zero is mapped to zero
+    pub fn inverse(self) -> Self {
+        self.invert().unwrap_or(self)
+    }
+
+    /// Multiply by the element of the base field
+    pub fn scale(self, other: F) -> Self {
+        Self::new(self.c0 * other, self.c1 * other, self.c2 * other)
+    }
+}
+
+impl<F: FieldValue, N: NonResidue<F>> From<F> for Fp6<F, N> {
+    fn from(c0: F) -> Self {
+        Self::new(c0, F::zero(), F::zero())
+    }
+}
+
+impl<F: FieldValue, N: NonResidue<F>> Add for Fp6<F, N> {
+    type Output = Self;
+    fn add(self, other: Self) -> Self {
+        Self::new(self.c0 + other.c0, self.c1 + other.c1, self.c2 + other.c2)
+    }
+}
+
+impl<F: FieldValue, N: NonResidue<F>> Sub for Fp6<F, N> {
+    type Output = Self;
+    fn sub(self, other: Self) -> Self {
+        Self::new(self.c0 - other.c0, self.c1 - other.c1, self.c2 - other.c2)
+    }
+}
+
+impl<F: FieldValue, N: NonResidue<F>> Neg for Fp6<F, N> {
+    type Output = Self;
+    fn neg(self) -> Self {
+        Self::new(-self.c0, -self.c1, -self.c2)
+    }
+}
+
+impl<F: FieldValue, N: NonResidue<F>> Mul for Fp6<F, N> {
+    type Output = Self;
+    fn mul(self, other: Self) -> Self {
+        // Karatsuba
+        let n = N::non_residue();
+
+        let v0 = self.c0 * other.c0;
+        let v1 = self.c1 * other.c1;
+        let v2 = self.c2 * other.c2;
+
+        Self::new(
+            v0 + n * ((self.c1 + self.c2) * (other.c1 + other.c2) - v1 - v2),
+            (self.c0 + self.c1) * (other.c0 + other.c1) - v0 - v1 + n * v2,
+            (self.c0 + self.c2) * (other.c0 + other.c2) - v0 - v2 + v1,
+        )
+    }
+}
+
+impl<F: FieldValue, N: NonResidue<F>> Mul<u32> for Fp6<F, N> {
+    type Output = Self;
+    fn mul(self, other: u32) -> Self {
+        Self::new(self.c0 * other, self.c1 * other, self.c2 * other)
+    }
+}
+
+impl<F: FieldValue, N: NonResidue<F>> Div for Fp6<F, N> {
+    type Output = Self;
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn div(self, other: Self) -> Self {
+        self * other.invert().expect("division by zero")
+    }
+}
+
+impl<F: FieldValue, N: NonResidue<F>> FieldValue for Fp6<F, N> {
+    type Value = F::Value;
+
+    fn characteristic() -> F::Value {
+        F::characteristic()
+    }
+
+    fn degree() -> usize {
+        3 * F::degree()
+    }
+
+    fn invert(self) -> Option<Self> {
+        let n = N::non_residue();
+
+        let t0 = self.c0.squared() - n * self.c1 * self.c2;
+        let t1 = n * self.c2.squared() - self.c0 * self.c1;
+        let t2 = self.c1.squared() - self.c0 * self.c2;
+
+        let det = self.c0 * t0 + n * (self.c2 * t1 + self.c1 * t2);
+        det.invert().map(|det_inv| Self::new(t0 * det_inv, t1 * det_inv, t2 * det_inv))
+    }
+
+    fn frobenius_map(self, power: usize) -> Self {
+        // v^(p^k) = v * N^((p^k - 1) / 3)
+        let power = power % Self::degree();
+        let coefficient = N::frobenius_coefficient(3, power);
+
+        Self::new(
+            self.c0.frobenius_map(power),
+            self.c1.frobenius_map(power) * coefficient,
+            self.c2.frobenius_map(power) * coefficient.squared(),
+        )
+    }
+
+    fn sqrt(self) -> Option<Self> {
+        if self == Self::zero() {
+            return Some(self);
+        }
+
+        // Odd degree extension over F, q = |F|:
+        //   b = x^((q^2 + q) / 2), b^2 * x = x^(q^2 + q + 1) = norm(x),
+        //   so (b * x)^2 = norm(x) * x and the root is b * x / sqrt(norm(x))
+        let q = F::degree();
+
+        let half = pow_minus_one_div(self, 2);
+        let x_half_q = (0..q).fold(Self::one(), |acc, i| acc * half.frobenius_map(i));
+        let b = (x_half_q * self).frobenius_map(q);
+
+        let norm = (b.squared() * self).c0;
+        let norm_root = norm.sqrt()?;
+
+        Some((b * self).scale(F::one() / norm_root))
+    }
+
+    fn legendre(self) -> i8 {
+        // odd degree extension: element is a square iff its norm is a square in the base field
+        self.norm().legendre()
+    }
+
+    #[cfg(feature = "rand")]
+    fn random<R: ::rand::RngCore + ?Sized>(rng: &mut R) -> Self {
+        Self::new(F::random(rng), F::random(rng), F::random(rng))
+    }
+
+    fn one() -> Self {
+        Self::new(F::one(), F::zero(), F::zero())
+    }
+
+    fn zero() -> Self {
+        Self::new(F::zero(), F::zero(), F::zero())
+    }
+}
+
+impl<F: FieldValue + ConditionallySelectable, N: NonResidue<F>> ConditionallySelectable for Fp6<F, N> {
+    fn conditional_select(a: &Self, b: &Self, choice: Choice) -> Self {
+        Self::new(
+            F::conditional_select(&a.c0, &b.c0, choice),
+            F::conditional_select(&a.c1, &b.c1, choice),
+            F::conditional_select(&a.c2, &b.c2, choice),
+        )
+    }
+}
+
+impl<F: FieldValue + ConstantTimeEq, N: NonResidue<F>> ConstantTimeEq for Fp6<F, N> {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.c0.ct_eq(&other.c0) & self.c1.ct_eq(&other.c1) & self.c2.ct_eq(&other.c2)
+    }
+}
+
+impl_field_ops!(impl<F: FieldValue, N: NonResidue<F>> Fp6<F, N>);
+
+#[cfg(test)]
+mod tests {
+
+    use super::{Fp6, Fp12, NonResidueCubeRoot};
+    use {FieldElement, FieldValue, Fp2, MinusOne, NonResidue, FrobeniusTable};
+    use fp2::frobenius_coefficient;
+    use test::{Mod19Field, Mod998244353Field};
+
+    use quickcheck::{Arbitrary, Gen};
+
+    type Fq = FieldElement<Mod19Field>;
+    type Fq2 = Fp2<Fq, MinusOne>;
+
+    /// 1 + u, neither a square nor a cube in Fp2 mod 19
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    struct OnePlusU;
+
+    impl NonResidue<Fq2> for OnePlusU {
+        fn non_residue() -> Fq2 {
+            Fp2::new(1.into(), 1.into())
+        }
+
+        fn frobenius_coefficient(degree: u32, power: usize) -> Fq2 {
+            static TABLE: FrobeniusTable<Fq2> = FrobeniusTable::new();
+            TABLE.get::<Self>(degree, power)
+        }
+    }
+
+    type Fq6 = Fp6<Fq2, OnePlusU>;
+    type Fq12 = Fp12<Fq2, OnePlusU>;
+
+    fn fq2<G: Gen>(g: &mut G) -> Fq2 {
+        Fp2::new(u64::arbitrary(g).into(), u64::arbitrary(g).into())
+    }
+
+    #[derive(Clone, Debug)]
+    struct Elem12(Fq12);
+
+    impl Arbitrary for Elem12 {
+        fn arbitrary<G: Gen>(g: &mut G) -> Self {
+            Elem12(Fp2::new(
+                Fp6::new(fq2(g), fq2(g), fq2(g)),
+                Fp6::new(fq2(g), fq2(g), fq2(g)),
+            ))
+        }
+    }
+
+    fn fq6(c: [u64; 6]) -> Fq6 {
+        Fp6::new(
+            Fp2::new(c[0].into(), c[1].into()),
+            Fp2::new(c[2].into(), c[3].into()),
+            Fp2::new(c[4].into(), c[5].into()),
+        )
+    }
+
+    #[test]
+    fn smoky() {
+        let a = fq6([1, 2, 3, 4, 5, 6]);
+        let b = fq6([7, 8, 9, 10, 11, 12]);
+
+        assert_eq!(a * b, b * a);
+        assert_eq!(a * a.inverse(), Fp6::one());
+        assert_eq!(a / b * b, a);
+        assert_eq!((a - a).invert(), None);
+        assert_eq!(a.squared(), a * a);
+
+        // v^3 = 1 + u
+        let v = fq6([0, 0, 1, 0, 0, 0]);
+        assert_eq!(v * v * v, fq6([1, 1, 0, 0, 0, 0]));
+    }
+
+    #[test]
+    fn frobenius() {
+        let a = fq6([1, 2, 3, 4, 5, 6]);
+
+        assert_eq!(a.frobenius_map(1), a.pow(19));
+        assert_eq!(a.frobenius_map(2), a.pow(19).pow(19));
+        assert_eq!(a.frobenius_map(6), a);
+        assert_eq!(a.frobenius_map(3).frobenius_map(3), a);
+
+        // w^2 = v
+        let w: Fq12 = Fp2::new(Fp6::zero(), Fp6::one());
+        assert_eq!(w.squared(), Fp2::new(fq6([0, 0, 1, 0, 0, 0]), Fp6::zero()));
+
+        let b: Fq12 = Fp2::new(a, fq6([7, 8, 9, 10, 11, 12]));
+        assert_eq!(b.frobenius_map(1), b.pow(19));
+        assert_eq!(b.frobenius_map(12), b);
+    }
+
+    #[test]
+    fn frobenius_coefficients() {
+        let n = OnePlusU::non_residue();
+
+        // cached table
+        assert_eq!(OnePlusU::frobenius_coefficient(3, 0), Fp2::one());
+        for k in 0..6 {
+            assert_eq!(OnePlusU::frobenius_coefficient(3, k), frobenius_coefficient(n, 3, k));
+            assert_eq!(OnePlusU::frobenius_coefficient(3, k + 6), frobenius_coefficient(n, 3, k));
+        }
+
+        // Fp12 coefficients through the sextic ones of 1 + u
+        for k in 0..12 {
+            let expected = frobenius_coefficient(<NonResidueCubeRoot as NonResidue<Fq6>>::non_residue(), 2, k);
+            assert_eq!(<NonResidueCubeRoot as NonResidue<Fq6>>::frobenius_coefficient(2, k), expected);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "requires p = 1 mod 3")]
+    fn frobenius_rejects_two_mod_three() {
+        type Fr = FieldElement<Mod998244353Field>;
+
+        // 998244353 = 2 mod 3, (p - 1) / 3 is not an integer
+        #[derive(Clone, Copy, Debug, PartialEq)]
+        struct Three;
+
+        impl NonResidue<Fr> for Three {
+            fn non_residue() -> Fr {
+                3.into()
+            }
+        }
+
+        Fp6::<Fr, Three>::new(1.into(), 2.into(), 3.into()).frobenius_map(1);
+    }
+
+    #[test]
+    fn norm_and_sqrt() {
+        let a = fq6([1, 2, 3, 4, 5, 6]);
+
+        assert_eq!(Fp6::from(a.norm()), a * a.pow(361) * a.pow(361).pow(361));
+
+        let root = a.squared().sqrt().unwrap();
+        assert!(root == a || root == -a);
+
+        // v is not a square, as 1 + u is not a square in Fp2
+        assert_eq!(fq6([0, 0, 1, 0, 0, 0]).sqrt(), None);
+        assert_eq!(fq6([0, 0, 1, 0, 0, 0]).legendre(), -1);
+    }
+
+    quickcheck! {
+        fn fp12_inverse(a: Elem12) -> bool {
+            let a = a.0;
+            a == Fp2::zero() || a * a.inverse() == Fp2::one()
+        }
+
+        fn fp12_mul_associative(a: Elem12, b: Elem12, c: Elem12) -> bool {
+            let (a, b, c) = (a.0, b.0, c.0);
+            (a * b) * c == a * (b * c)
+        }
+
+        fn fp12_frobenius_is_pow(a: Elem12) -> bool {
+            let a = a.0;
+            a.frobenius_map(1) == a.pow(19) && a.frobenius_map(5) == a.frobenius_map(2).frobenius_map(3)
+        }
+
+        fn fp12_sqrt_of_square(a: Elem12) -> bool {
+            let a = a.0;
+            let root = a.squared().sqrt().unwrap();
+            root == a || root == -a
+        }
+
+        fn fp6_is_square(a: Elem12) -> bool {
+            let a = a.0.c0();
+            a.is_square() == a.sqrt().is_some() && a.squared().is_square()
+        }
+    }
+}
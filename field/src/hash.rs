@@ -0,0 +1,154 @@
+//! Hashing to prime fields (RFC 9380)
+
+use sha2::{Digest, Sha256};
+
+use arith::{Scalar, ModMul};
+use {field, FieldElement};
+
+/// SHA-256 output size, in bytes
+const B_IN_BYTES: usize = 32;
+/// SHA-256 input block size, in bytes
+const S_IN_BYTES: usize = 64;
+/// Target security level, in bits
+const SECURITY_BITS: usize = 128;
+
+/// Domain separation tags longer than 255 bytes are hashed down (RFC 9380, section 5.3.3)
+fn reduce_dst(dst: &[u8]) -> Vec<u8> {
+    if dst.len() <= 255 {
+        return dst.to_vec();
+    }
+
+    let mut hasher = Sha256::new();
+    hasher.update(b"H2C-OVERSIZE-DST-");
+    hasher.update(dst);
+    hasher.finalize().to_vec()
+}
+
+/// expand_message_xmd over SHA-256 (RFC 9380, section 5.3.1)
+///
+/// Panics if `len_in_bytes` exceeds 255 * 32 bytes.
+pub fn expand_message_xmd(msg: &[u8], dst: &[u8], len_in_bytes: usize) -> Vec<u8> {
+    let ell = len_in_bytes.div_ceil(B_IN_BYTES);
+    assert!(ell <= 255, "expand_message_xmd output is limited to {} bytes", 255 * B_IN_BYTES);
+
+    let dst = reduce_dst(dst);
+    let dst_len = [dst.len() as u8];
+
+    // b_0 = H(Z_pad || msg || I2OSP(len_in_bytes, 2) || I2OSP(0, 1) || DST_prime)
+    let b_0 = Sha256::new()
+        .chain_update([0u8; S_IN_BYTES])
+        .chain_update(msg)
+        .chain_update((len_in_bytes as u16).to_be_bytes())
+        .chain_update([0u8])
+        .chain_update(&dst)
+        .chain_update(dst_len)
+        .finalize();
+
+    // b_1 = H(b_0 || I2OSP(1, 1) || DST_prime)
+    let mut b_i = Sha256::new()
+        .chain_update(b_0)
+        .chain_update([1u8])
+        .chain_update(&dst)
+        .chain_update(dst_len)
+        .finalize();
+
+    let mut uniform_bytes = Vec::with_capacity(ell * B_IN_BYTES);
+    uniform_bytes.extend_from_slice(&b_i);
+
+    // b_i = H(strxor(b_0, b_(i - 1)) || I2OSP(i, 1) || DST_prime)
+    for i in 2..=ell {
+        let mut xored = [0u8; B_IN_BYTES];
+        for (x, (a, b)) in xored.iter_mut().zip(b_0.iter().zip(b_i.iter())) {
+            *x = a ^ b;
+        }
+
+        b_i = Sha256::new()
+            .chain_update(xored)
+            .chain_update([i as u8])
+            .chain_update(&dst)
+            .chain_update(dst_len)
+            .finalize();
+        uniform_bytes.extend_from_slice(&b_i);
+    }
+
+    uniform_bytes.truncate(len_in_bytes);
+    uniform_bytes
+}
+
+/// Big-endian bytes of arbitrary length modulo the modulus, Horner's rule over bytes
+fn reduce_bytes<V: Scalar>(bytes: &[u8], modulus: V) -> V {
+    let mut buf = vec![0u8; V::max_bits() / 8];
+    let last = buf.len() - 1;
+
+    bytes.iter().fold(V::zero(), |acc, &b| {
+        buf[last] = b;
+        ModMul::<u32>::mul(acc, 256, modulus).add(V::read_be_bytes(&buf) % modulus, modulus)
+    })
+}
+
+/// Hash message to `count` elements of the prime field F with expand_message_xmd over SHA-256
+/// (RFC 9380, section 5.2)
+///
+/// Each element is reduced from L = ceil((ceil(log2(p)) + 128) / 8) uniform bytes, so the
+/// result is statistically close to uniform.
+pub fn hash_to_field<F: field::Field>(msg: &[u8], dst: &[u8], count: usize) -> Vec<FieldElement<F>> {
+    let modulus_bits = (0..F::Value::max_bits()).rev().find(|&i| F::MODULUS.bit(i)).map_or(0, |i| i + 1);
+    let l = (modulus_bits + SECURITY_BITS).div_ceil(8);
+
+    expand_message_xmd(msg, dst, count * l)
+        .chunks(l)
+        .map(|chunk| reduce_bytes(chunk, F::MODULUS).into())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+
+    extern crate serde_json;
+
+    use super::{expand_message_xmd, reduce_bytes};
+    use test::Mod19Field;
+
+    fn from_hex(s: &str) -> Vec<u8> {
+        (0..s.len()).step_by(2).map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap()).collect()
+    }
+
+    #[test]
+    fn expand_message_xmd_rfc_vectors() {
+        let data: serde_json::Value = serde_json::from_str(include_str!("../tests/data/expand_message_xmd_SHA256.json")).unwrap();
+        let tests = data["tests"].as_array().unwrap();
+        assert_eq!(tests.len(), 20);
+
+        for test in tests {
+            let dst = test["DST"].as_str().unwrap().as_bytes();
+            let msg = test["msg"].as_str().unwrap().as_bytes();
+            let len = test["len_in_bytes"].as_u64().unwrap() as usize;
+
+            assert_eq!(expand_message_xmd(msg, dst, len), from_hex(test["uniform_bytes"].as_str().unwrap()));
+        }
+    }
+
+    #[test]
+    fn reduce() {
+        assert_eq!(reduce_bytes(&[], 19u64), 0);
+        assert_eq!(reduce_bytes(&[1, 0], 19u64), 256 % 19);
+        assert_eq!(reduce_bytes(&[0xff; 48], 1125899839733759u64), {
+            // (2^384 - 1) mod p by repeated squaring of 2^64 mod p
+            let p = 1125899839733759u128;
+            let r = (1u128 << 64) % p;
+            ((r * r % p * r % p * r % p * r % p * r % p + p - 1) % p) as u64
+        });
+    }
+
+    #[test]
+    fn hash_to_small_field() {
+        let elements = super::hash_to_field::<Mod19Field>(b"abc", b"QUUX-V01-CS02-with-expander-SHA256-128", 3);
+
+        assert_eq!(elements.len(), 3);
+        // L = ceil((5 + 128) / 8) = 17 bytes per element
+        let uniform = expand_message_xmd(b"abc", b"QUUX-V01-CS02-with-expander-SHA256-128", 51);
+        for (element, chunk) in elements.into_iter().zip(uniform.chunks(17)) {
+            assert_eq!(element.into_value(), reduce_bytes(chunk, 19u64));
+        }
+    }
+}
@@ -1,4 +1,5 @@
 use arith::*;
+use safegcd;
 
 impl ModMul for u64 {
     fn mul(self, other: Self, module: Self) -> Self {
@@ -7,25 +8,37 @@ impl ModMul for u64 {
 }
 
 impl MulReduce for u64 {
-    fn mul_reduce(self, other: Self, module: Self, r_inverse: Self) -> Self {
+    fn mul_reduce(self, other: Self, module: Self, r_inverse: Self, _inv: u64) -> Self {
         self.mul(other, module).mul(r_inverse, module)
     }
 }
 
+impl BarrettMul for u64 {
+    fn mul_barrett(self, other: Self, module: Self, mu: Self) -> Self {
+        let k = 64 - module.leading_zeros();
+        let x = (self as u128) * (other as u128);
+
+        // x < module^2 < 2^(2k), so x1 < 2^k fits the word
+        let x1 = (x >> k) as u64;
+        let q = (x1 as u128) + (((x1 as u128) * (mu as u128)) >> k);
+
+        let mut r = x - q * (module as u128);
+        while r >= module as u128 {
+            r -= module as u128;
+        }
+        r as u64
+    }
+}
+
 impl ModAdd for u64 {
     fn add(self, other: Self, module: Self) -> Self {
-        let (res, overflow) = self.overflowing_add(other);
-        if overflow {
-            u64::max_value() % module + 1
-        } else {
-            res % module
-        }
+        (((self as u128) + (other as u128)) % (module as u128)) as u64
     }
 }
 
 impl ModNeg for u64 {
     fn neg(self, module: Self) -> Self {
-        module - (self % module)
+        (module - self % module) % module
     }
 }
 
@@ -48,6 +61,38 @@ impl ModInv for u64 {
     }
 }
 
+impl ModInvCt for u64 {
+    fn inv_ct(self, module: Self) -> Self {
+        safegcd::safegcd_inverse([self], [module])[0]
+    }
+}
+
+impl Jacobi for u64 {
+    fn jacobi(self, module: Self) -> i8 {
+        let mut a = self % module;
+        let mut n = module;
+        let mut t = 1;
+
+        while a != 0 {
+            while a & 1 == 0 {
+                a >>= 1;
+                // (2 / n) = -1 when n = 3, 5 mod 8
+                if n & 7 == 3 || n & 7 == 5 { t = -t; }
+            }
+
+            if a < n {
+                ::std::mem::swap(&mut a, &mut n);
+                // quadratic reciprocity
+                if a & 3 == 3 && n & 3 == 3 { t = -t; }
+            }
+
+            a -= n;
+        }
+
+        if n == 1 { t } else { 0 }
+    }
+}
+
 impl ModMul<u32> for u64 {
     fn mul(self, scalar: u32, module: Self) -> Self {
         self.mul(scalar as u64, module)
@@ -70,4 +115,14 @@ impl Scalar for u64 {
     fn bit(&self, position: usize) -> bool {
         self & (1 << position) > 0
     }
+
+    fn write_be_bytes(&self, bytes: &mut [u8]) {
+        bytes.copy_from_slice(&self.to_be_bytes());
+    }
+
+    fn read_be_bytes(bytes: &[u8]) -> u64 {
+        let mut buf = [0u8; 8];
+        buf.copy_from_slice(bytes);
+        u64::from_be_bytes(buf)
+    }
 }
\ No newline at end of file
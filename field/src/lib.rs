@@ -3,17 +3,59 @@
 #![warn(missing_docs)]
 
 #[cfg(test)] #[macro_use] extern crate quickcheck;
+#[cfg(feature = "hash")] extern crate sha2;
+#[cfg(feature = "serde")] extern crate serde;
+#[cfg(any(test, feature = "rand"))] extern crate rand;
 
+#[macro_use]
+mod ops;
 mod field;
 mod element;
 mod arith;
 mod impls;
 mod mont;
+mod barrett;
+mod ct;
+mod ct_mont;
+mod sqrt;
+mod power;
+mod safegcd;
+mod batch;
+mod fp2;
+mod fp6;
+mod binary;
+mod dynamic;
+mod constants;
+mod encoding;
+mod text;
+#[cfg(feature = "hash")]
+mod hash;
+#[cfg(feature = "serde")]
+mod serialization;
 #[cfg(test)]
 mod test;
 
-pub use arith::{MulReduce, ModMul, ModAdd, ModInv, ModNeg};
+pub use arith::{MulReduce, BarrettMul, ModMul, ModAdd, ModInv, ModInvCt, ModNeg, Jacobi};
 pub use arith::Scalar;
-pub use field::{Field, FieldValue};
+#[cfg(feature = "rand")]
+pub use rand::RngCore;
+pub use field::{Field, MontgomeryField, BarrettField, FieldValue};
 pub use element::FieldElement;
-pub use mont::MontgomeryElement;
\ No newline at end of file
+pub use mont::MontgomeryElement;
+pub use barrett::BarrettElement;
+pub use ct::{Choice, ConditionallySelectable, ConstantTimeEq, ConstantTimeArith};
+pub use ct_mont::ConstantTimeElement;
+pub use batch::batch_invert;
+pub use safegcd::safegcd_inverse;
+pub use power::DEFAULT_WINDOW;
+pub use fp2::{Fp2, NonResidue, MinusOne, FrobeniusTable};
+pub use fp6::{Fp6, Fp12, NonResidueCubeRoot};
+pub use binary::{BinaryField, BinaryFieldElement};
+pub use dynamic::{DynamicField, DynamicElement, ModulusError};
+pub use encoding::DecodeError;
+pub use text::ParseError;
+#[cfg(feature = "hash")]
+pub use hash::{hash_to_field, expand_message_xmd};
+#[cfg(feature = "serde")]
+pub use serialization::{serialize_fixed_bytes, deserialize_fixed_bytes};
+pub use constants::{montgomery_r, montgomery_r2, montgomery_r_inverse, montgomery_inv, montgomery_constants_valid, barrett_mu};
\ No newline at end of file
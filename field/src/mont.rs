@@ -1,17 +1,38 @@
 //! Abstract montgomery field element
 
+use std::fmt;
 use std::ops::{Add, Mul, Neg, Sub, Div};
+use std::str::FromStr;
 
 use arith::{self, Scalar, ModAdd, ModMul, ModNeg, ModInv, MulReduce};
-use {field, element};
+use ct::{Choice, ConditionallySelectable, ConstantTimeEq};
+use {field, element, sqrt, encoding, text};
 
 /// Field element on the field F with value V in montgomery representation
+///
+/// Only fields with montgomery parameters can be used:
+///
+/// ```compile_fail
+/// # extern crate ng_field;
+/// # use ng_field::{Field, MontgomeryElement};
+/// #[derive(Clone, Copy, Debug, PartialEq)]
+/// struct Mod19Field;
+///
+/// impl Field for Mod19Field {
+///     type Value = u64;
+///     const MODULUS: u64 = 19;
+/// }
+///
+/// # fn main() {
+/// let _: MontgomeryElement<Mod19Field> = 6.into();
+/// # }
+/// ```
 #[derive(Clone, Copy, Debug, PartialEq)]
-pub struct MontgomeryElement<F: field::Field> {
+pub struct MontgomeryElement<F: field::MontgomeryField> {
     value: F::Value,
 }
 
-impl<F: field::Field> field::FieldValue for MontgomeryElement<F> {
+impl<F: field::MontgomeryField> field::FieldValue for MontgomeryElement<F> {
     type Value = F::Value;
 
     /// Multiplication identity
@@ -27,9 +48,27 @@ impl<F: field::Field> field::FieldValue for MontgomeryElement<F> {
             value: F::Value::zero(),
         }
     }
+
+    fn characteristic() -> F::Value {
+        F::MODULUS
+    }
+
+    fn sqrt(self) -> Option<Self> {
+        sqrt::sqrt(self, Self::one(), F::MODULUS)
+    }
+
+    fn legendre(self) -> i8 {
+        sqrt::legendre(self, Self::one(), F::MODULUS)
+    }
+
+    #[cfg(feature = "rand")]
+    fn random<R: ::rand::RngCore + ?Sized>(rng: &mut R) -> Self {
+        // uniform in montgomery form as well, x -> x * R is a bijection
+        MontgomeryElement::from_raw(F::Value::random_below(rng, F::MODULUS))
+    }
 }
 
-impl<F: field::Field> MontgomeryElement<F> {
+impl<F: field::MontgomeryField> MontgomeryElement<F> {
     /// New field element from regular form
     pub fn from_element(t: element::FieldElement<F>) -> Self {
         t.into_value().into()
@@ -45,11 +84,31 @@ impl<F: field::Field> MontgomeryElement<F> {
         self.value
     }
 
-    /// Deconstruct and return raw value (not reduced)
+    /// Deconstruct and return reduced value
     pub fn into_reduced_value(self) -> F::Value {
         ModMul::<F::Value>::mul(self.value, F::R_INVERSE, F::MODULUS)
     }
 
+    /// Fixed-width big-endian bytes of the reduced value
+    pub fn to_bytes_be(&self) -> Vec<u8> {
+        encoding::encode(self.into_reduced_value(), false)
+    }
+
+    /// Fixed-width little-endian bytes of the reduced value
+    pub fn to_bytes_le(&self) -> Vec<u8> {
+        encoding::encode(self.into_reduced_value(), true)
+    }
+
+    /// New element from fixed-width big-endian bytes of the reduced value, values not less than MODULUS are rejected
+    pub fn from_bytes_be(bytes: &[u8]) -> Result<Self, encoding::DecodeError> {
+        encoding::decode(bytes, F::MODULUS, false).map(Into::into)
+    }
+
+    /// New element from fixed-width little-endian bytes of the reduced value, values not less than MODULUS are rejected
+    pub fn from_bytes_le(bytes: &[u8]) -> Result<Self, encoding::DecodeError> {
+        encoding::decode(bytes, F::MODULUS, true).map(Into::into)
+    }
+
     /// Construct from raw value (should be reduced in advance)
     pub(crate) fn from_raw(val: F::Value) -> Self {
         MontgomeryElement {
@@ -58,49 +117,74 @@ impl<F: field::Field> MontgomeryElement<F> {
     }
 }
 
-impl<F: field::Field> Add for MontgomeryElement<F> {
+impl<F: field::MontgomeryField> FromStr for MontgomeryElement<F> {
+    type Err = text::ParseError;
+
+    /// Parse decimal or `0x`-prefixed hex reduced value, values not less than MODULUS are rejected
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        text::parse_canonical(s, F::MODULUS).map(Into::into)
+    }
+}
+
+impl<F: field::MontgomeryField> fmt::Display for MontgomeryElement<F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.into_reduced_value().fmt_decimal(f)
+    }
+}
+
+impl<F: field::MontgomeryField> fmt::LowerHex for MontgomeryElement<F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.into_reduced_value().fmt_lower_hex(f)
+    }
+}
+
+impl<F: field::MontgomeryField> Add for MontgomeryElement<F> {
     type Output = Self;
     fn add(self, other: Self) -> Self::Output {
         MontgomeryElement::from_raw(self.value.add(other.value, F::MODULUS))
     }
 }
 
-impl<F: field::Field> Sub for MontgomeryElement<F> {
+impl<F: field::MontgomeryField> Sub for MontgomeryElement<F> {
     type Output = Self;
     fn sub(self, other: Self) -> Self::Output {
         MontgomeryElement::from_raw(self.value.add(other.value.neg(F::MODULUS), F::MODULUS))
     }
 }
 
-impl<F: field::Field> Neg for MontgomeryElement<F> {
+impl<F: field::MontgomeryField> Neg for MontgomeryElement<F> {
     type Output = Self;
     fn neg(self) -> Self::Output {
         MontgomeryElement::from_raw(self.value.neg(F::MODULUS))
     }
 }
 
-impl<F: field::Field> Mul for MontgomeryElement<F> {
+impl<F: field::MontgomeryField> Mul for MontgomeryElement<F> {
     type Output = Self;
     fn mul(self, other: Self) -> Self::Output {
-        MontgomeryElement::from_raw(self.value.mul_reduce(other.value, F::MODULUS, F::R_INVERSE))
+        MontgomeryElement::from_raw(self.value.mul_reduce(other.value, F::MODULUS, F::R_INVERSE, F::INV))
     }
 }
 
-impl<F: field::Field> Mul<u32> for MontgomeryElement<F> {
+impl<F: field::MontgomeryField> Mul<u32> for MontgomeryElement<F> {
     type Output = Self;
     fn mul(self, other: u32) -> Self {
         MontgomeryElement::from_raw(self.value.mul(other, F::MODULUS))
     }
 }
 
-impl<F: field::Field> Div for MontgomeryElement<F> {
+impl<F: field::MontgomeryField> Div for MontgomeryElement<F> {
     type Output = Self;
     fn div(self, other: Self) -> Self::Output {
-        MontgomeryElement::from_raw(self.value.mul_reduce(other.value.inv(F::MODULUS), F::MODULUS, F::R))
+        assert!(other.value != F::Value::zero(), "division by zero");
+        // (a * R) * b^-1 = (a / b) * R
+        MontgomeryElement::from_raw(
+            ModMul::<F::Value>::mul(self.value, other.into_reduced_value().inv(F::MODULUS), F::MODULUS)
+        )
     }
 }
 
-impl<F: field::Field<Value=V>, V: arith::Scalar> From<V> for MontgomeryElement<F>
+impl<F: field::MontgomeryField<Value=V>, V: arith::Scalar> From<V> for MontgomeryElement<F>
 {
     fn from(val: V) -> Self {
         MontgomeryElement {
@@ -109,6 +193,19 @@ impl<F: field::Field<Value=V>, V: arith::Scalar> From<V> for MontgomeryElement<F
     }
 }
 
+impl<F: field::MontgomeryField> ConditionallySelectable for MontgomeryElement<F> where F::Value: ConditionallySelectable {
+    fn conditional_select(a: &Self, b: &Self, choice: Choice) -> Self {
+        MontgomeryElement { value: F::Value::conditional_select(&a.value, &b.value, choice) }
+    }
+}
+
+impl<F: field::MontgomeryField> ConstantTimeEq for MontgomeryElement<F> where F::Value: ConstantTimeEq {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.value.ct_eq(&other.value)
+    }
+}
+
+impl_field_ops!(impl<F: field::MontgomeryField> MontgomeryElement<F>);
 
 #[cfg(test)]
 mod tests {
@@ -120,11 +217,12 @@ mod tests {
 
     #[test]
     fn smoky() {
+        // R = 2^64 = 17 mod 19
         let elem1: MontgomeryElement<Mod19Field> = 6.into();
-        assert_eq!(elem1.into_value(), 1);
+        assert_eq!(elem1.into_value(), 7);
 
         let elem2: MontgomeryElement<Mod19Field> = 16.into();
-        assert_eq!(elem2.into_value(), 9);
+        assert_eq!(elem2.into_value(), 6);
 
         assert_eq!(elem1 + elem2, 3.into());
         assert_eq!(elem2 + elem1, 3.into());
@@ -148,16 +246,63 @@ mod tests {
      #[test]
      fn mul_scalar() {
          let elem1: MontgomeryElement<Mod19Field> = 6.into();
-         assert_eq!(elem1.clone().into_value(), 1);
-         assert_eq!((elem1 * 2).into_value(), 2);
+         assert_eq!(elem1.into_value(), 7);
+         assert_eq!((elem1 * 2).into_value(), 14);
      }
 
+     #[test]
+     fn neg_zero() {
+         let zero: MontgomeryElement<Mod19Field> = MontgomeryElement::zero();
+         assert_eq!((-zero).into_value(), 0);
+         assert_eq!(-zero, zero);
+     }
+
+    #[test]
+    fn strings() {
+        use ParseError;
+
+        // montgomery form of 6 is 7, canonical value is printed
+        let elem: MontgomeryElement<Mod19Field> = "6".parse().unwrap();
+        assert_eq!(elem.into_value(), 7);
+        assert_eq!(format!("{}", elem), "6");
+        assert_eq!(format!("{:x}", MontgomeryElement::<Mod19Field>::from(18)), "12");
+        assert_eq!("0x12".parse(), Ok(MontgomeryElement::<Mod19Field>::from(18)));
+
+        assert_eq!("19".parse::<MontgomeryElement<Mod19Field>>(), Err(ParseError::NotCanonical));
+    }
+
+    #[cfg(feature = "rand")]
+    #[test]
+    fn random_is_uniform() {
+        use rand::{SeedableRng, rngs::StdRng};
+
+        let mut rng = StdRng::seed_from_u64(19);
+        let mut counts = [0usize; 19];
+        for _ in 0..19000 {
+            counts[MontgomeryElement::<Mod19Field>::random(&mut rng).into_reduced_value() as usize] += 1;
+        }
+
+        assert!(counts.iter().all(|&c| c > 850 && c < 1150), "{:?}", counts);
+    }
+
     fn field1_elem<T: Into<MontgomeryElement<Mod1125899839733759Field>>>(v: T) -> MontgomeryElement<Mod1125899839733759Field>
     {
         v.into()
     }
 
      quickcheck! {
+        fn bytes_encode_reduced_value(x: u64) -> bool {
+            use FieldElement;
+
+            let x_e = field1_elem(x);
+            let bytes = x_e.to_bytes_be();
+
+            bytes == (x % 1125899839733759).to_be_bytes() &&
+                bytes == FieldElement::<Mod1125899839733759Field>::from(x).to_bytes_be() &&
+                MontgomeryElement::from_bytes_be(&bytes) == Ok(x_e) &&
+                MontgomeryElement::from_bytes_le(&x_e.to_bytes_le()) == Ok(x_e)
+        }
+
         fn number_div_by_self_equals_one(x: u64) -> TestResult {
             if x % 1125899839733759 == 0 {
                 TestResult::discard()
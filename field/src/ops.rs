@@ -0,0 +1,122 @@
+//! Operator-assign, reference and iterator traits derived from by-value arithmetic
+
+/// Implement `AddAssign`/`SubAssign`/`MulAssign`/`DivAssign`, operators on references,
+/// `Sum` and `Product` for a `Copy` field value type from its by-value operators
+///
+/// These are required by `FieldValue`, so every implementation outside of this crate
+/// should invoke the macro as well:
+///
+/// ```
+/// #[macro_use] extern crate ng_field;
+/// # use std::ops::{Add, Mul, Neg, Sub, Div};
+/// # use ng_field::{Field, FieldElement, FieldValue};
+/// # #[derive(Clone, Copy, Debug, PartialEq)]
+/// # pub struct Mod19Field;
+/// # impl Field for Mod19Field { type Value = u64; const MODULUS: u64 = 19; }
+///
+/// #[derive(Clone, Copy, Debug, PartialEq)]
+/// pub struct Element(FieldElement<Mod19Field>);
+/// # impl Add for Element { type Output = Self; fn add(self, o: Self) -> Self { Element(self.0 + o.0) } }
+/// # impl Sub for Element { type Output = Self; fn sub(self, o: Self) -> Self { Element(self.0 - o.0) } }
+/// # impl Mul for Element { type Output = Self; fn mul(self, o: Self) -> Self { Element(self.0 * o.0) } }
+/// # impl Mul<u32> for Element { type Output = Self; fn mul(self, o: u32) -> Self { Element(self.0 * o) } }
+/// # impl Div for Element { type Output = Self; fn div(self, o: Self) -> Self { Element(self.0 / o.0) } }
+/// # impl Neg for Element { type Output = Self; fn neg(self) -> Self { Element(-self.0) } }
+/// # impl FieldValue for Element {
+/// #     type Value = u64;
+/// #     fn characteristic() -> u64 { 19 }
+/// #     fn sqrt(self) -> Option<Self> { self.0.sqrt().map(Element) }
+/// #     fn legendre(self) -> i8 { self.0.legendre() }
+/// #     fn one() -> Self { Element(FieldElement::one()) }
+/// #     fn zero() -> Self { Element(FieldElement::zero()) }
+/// # }
+///
+/// impl_field_ops!(Element);
+///
+/// # fn main() {
+/// let elements = [Element(2.into()), Element(3.into())];
+/// assert_eq!(elements.iter().product::<Element>(), Element(6.into()));
+/// # }
+/// ```
+///
+/// Generic types list their parameters first, with an optional where clause at the end:
+/// `impl_field_ops!(impl<F: Field> FieldElement<F>)`.
+#[macro_export]
+macro_rules! impl_field_ops {
+    (impl<$($param:ident: $bound:path),*> $t:ty $(where $($clause:tt)+)?) => {
+        $crate::impl_field_ops!(@binary [$($param: $bound),*] $t [$($($clause)+)?]; Add, add, AddAssign, add_assign);
+        $crate::impl_field_ops!(@binary [$($param: $bound),*] $t [$($($clause)+)?]; Sub, sub, SubAssign, sub_assign);
+        $crate::impl_field_ops!(@binary [$($param: $bound),*] $t [$($($clause)+)?]; Mul, mul, MulAssign, mul_assign);
+        $crate::impl_field_ops!(@binary [$($param: $bound),*] $t [$($($clause)+)?]; Div, div, DivAssign, div_assign);
+
+        impl<'a, $($param: $bound),*> ::std::ops::Neg for &'a $t where $($($clause)+)? {
+            type Output = $t;
+            fn neg(self) -> $t {
+                -*self
+            }
+        }
+
+        impl<$($param: $bound),*> ::std::iter::Sum for $t where $($($clause)+)? {
+            fn sum<I: Iterator<Item = $t>>(iter: I) -> $t {
+                iter.fold(<$t as $crate::FieldValue>::zero(), |acc, x| acc + x)
+            }
+        }
+
+        impl<'a, $($param: $bound),*> ::std::iter::Sum<&'a $t> for $t where $($($clause)+)? {
+            fn sum<I: Iterator<Item = &'a $t>>(iter: I) -> $t {
+                iter.fold(<$t as $crate::FieldValue>::zero(), |acc, x| acc + *x)
+            }
+        }
+
+        impl<$($param: $bound),*> ::std::iter::Product for $t where $($($clause)+)? {
+            fn product<I: Iterator<Item = $t>>(iter: I) -> $t {
+                iter.fold(<$t as $crate::FieldValue>::one(), |acc, x| acc * x)
+            }
+        }
+
+        impl<'a, $($param: $bound),*> ::std::iter::Product<&'a $t> for $t where $($($clause)+)? {
+            fn product<I: Iterator<Item = &'a $t>>(iter: I) -> $t {
+                iter.fold(<$t as $crate::FieldValue>::one(), |acc, x| acc * *x)
+            }
+        }
+    };
+
+    (@binary [$($generics: tt)*] $t: ty [$($clause: tt)*]; $op: ident, $method: ident, $op_assign: ident, $method_assign: ident) => {
+        impl<$($generics)*> ::std::ops::$op_assign for $t where $($clause)* {
+            fn $method_assign(&mut self, other: $t) {
+                *self = ::std::ops::$op::$method(*self, other);
+            }
+        }
+
+        impl<'a, $($generics)*> ::std::ops::$op_assign<&'a $t> for $t where $($clause)* {
+            fn $method_assign(&mut self, other: &'a $t) {
+                *self = ::std::ops::$op::$method(*self, *other);
+            }
+        }
+
+        impl<'a, $($generics)*> ::std::ops::$op<&'a $t> for $t where $($clause)* {
+            type Output = $t;
+            fn $method(self, other: &'a $t) -> $t {
+                ::std::ops::$op::$method(self, *other)
+            }
+        }
+
+        impl<'a, $($generics)*> ::std::ops::$op<$t> for &'a $t where $($clause)* {
+            type Output = $t;
+            fn $method(self, other: $t) -> $t {
+                ::std::ops::$op::$method(*self, other)
+            }
+        }
+
+        impl<'a, 'b, $($generics)*> ::std::ops::$op<&'b $t> for &'a $t where $($clause)* {
+            type Output = $t;
+            fn $method(self, other: &'b $t) -> $t {
+                ::std::ops::$op::$method(*self, *other)
+            }
+        }
+    };
+
+    ($t:ty) => {
+        $crate::impl_field_ops!(impl<> $t);
+    };
+}
@@ -0,0 +1,219 @@
+//! Windowed exponentiation
+
+use std::ops::Mul;
+
+use arith::Scalar;
+use ct::{ConditionallySelectable, ConstantTimeEq};
+
+/// Default window width of `FieldValue::pow` and `FieldValue::pow_vartime`
+pub const DEFAULT_WINDOW: usize = 4;
+
+fn check_width(width: usize) {
+    assert!((1..=8).contains(&width), "window width should be within 1..=8, got {}", width);
+}
+
+/// `width` bits of `exp` starting at `position`, bits past the top are zero
+///
+/// Only the positions are branched on, not the bits.
+fn digit<V: Scalar>(exp: &V, position: usize, width: usize) -> usize {
+    (0..width)
+        .filter(|&j| position + j < V::max_bits())
+        .fold(0, |acc, j| acc | (exp.bit(position + j) as usize) << j)
+}
+
+/// base^exp by fixed-window exponentiation, `one` is the multiplicative identity
+///
+/// Always performs the same sequence of squarings and multiplications for the given value
+/// type, whatever the exponent is: a table of base^0..base^(2^width - 1) is multiplied in
+/// once per `width` bits.
+pub(crate) fn pow_fixed<T: Clone + Mul<Output=T>, V: Scalar>(base: T, exp: V, one: T, width: usize) -> T {
+    check_width(width);
+
+    let mut table = Vec::with_capacity(1 << width);
+    table.push(one);
+    for i in 1..1 << width {
+        table.push(table[i - 1].clone() * base.clone());
+    }
+
+    let chunks = V::max_bits().div_ceil(width);
+    let mut res = table[digit(&exp, (chunks - 1) * width, width)].clone();
+
+    for chunk in (0..chunks - 1).rev() {
+        for _ in 0..width {
+            res = res.clone() * res;
+        }
+        res = res * table[digit(&exp, chunk * width, width)].clone();
+    }
+
+    res
+}
+
+/// base^exp by fixed-window exponentiation without secret-dependent table indices
+///
+/// Same sequence of operations as `pow_fixed`, but every lookup reads the whole table and
+/// keeps the wanted entry with a conditional select.
+pub(crate) fn pow_fixed_ct<T, V>(base: T, exp: V, one: T, width: usize) -> T
+    where T: Clone + Mul<Output=T> + ConditionallySelectable, V: Scalar
+{
+    check_width(width);
+
+    let mut table = Vec::with_capacity(1 << width);
+    table.push(one);
+    for i in 1..1 << width {
+        table.push(table[i - 1].clone() * base.clone());
+    }
+
+    let lookup = |d: usize| {
+        table.iter().enumerate().skip(1).fold(table[0].clone(), |acc, (i, entry)| {
+            T::conditional_select(&acc, entry, (i as u64).ct_eq(&(d as u64)))
+        })
+    };
+
+    let chunks = V::max_bits().div_ceil(width);
+    let mut res = lookup(digit(&exp, (chunks - 1) * width, width));
+
+    for chunk in (0..chunks - 1).rev() {
+        for _ in 0..width {
+            res = res.clone() * res;
+        }
+        res = res * lookup(digit(&exp, chunk * width, width));
+    }
+
+    res
+}
+
+/// base^exp by sliding-window exponentiation, `one` is the multiplicative identity
+///
+/// Skips leading zero bits and zero runs between windows, so the running time depends on
+/// the exponent: use with public exponents only.
+pub(crate) fn pow_sliding<T: Clone + Mul<Output=T>, V: Scalar>(base: T, exp: V, one: T, width: usize) -> T {
+    check_width(width);
+
+    let top = match (0..V::max_bits()).rev().find(|&i| exp.bit(i)) {
+        Some(top) => top,
+        None => return one,
+    };
+
+    // odd powers base, base^3, ..., base^(2^width - 1)
+    let squared = base.clone() * base.clone();
+    let mut table = Vec::with_capacity(1 << (width - 1));
+    table.push(base);
+    for i in 1..1 << (width - 1) {
+        table.push(table[i - 1].clone() * squared.clone());
+    }
+
+    let mut res: Option<T> = None;
+    let mut i = top as isize;
+
+    while i >= 0 {
+        if !exp.bit(i as usize) {
+            res = res.map(|r| r.clone() * r);
+            i -= 1;
+            continue;
+        }
+
+        // longest window [low, i] of at most `width` bits ending with a set bit
+        let mut low = ::std::cmp::max(i - width as isize + 1, 0);
+        while !exp.bit(low as usize) {
+            low += 1;
+        }
+
+        let len = (i - low + 1) as usize;
+        let odd = table[digit(&exp, low as usize, len) >> 1].clone();
+        res = Some(match res {
+            Some(mut r) => {
+                for _ in 0..len {
+                    r = r.clone() * r;
+                }
+                r * odd
+            },
+            None => odd,
+        });
+
+        i = low - 1;
+    }
+
+    res.unwrap_or(one)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::{pow_fixed, pow_fixed_ct, pow_sliding};
+    use ct::{Choice, ConditionallySelectable};
+
+    use std::cell::Cell;
+
+    thread_local! {
+        static MULTIPLICATIONS: Cell<usize> = const { Cell::new(0) };
+    }
+
+    /// Integers modulo 1000003, counting multiplications
+    #[derive(Clone, Debug, PartialEq)]
+    struct Counted(u64);
+
+    impl ::std::ops::Mul for Counted {
+        type Output = Counted;
+        fn mul(self, other: Counted) -> Counted {
+            MULTIPLICATIONS.with(|m| m.set(m.get() + 1));
+            Counted(self.0 * other.0 % 1000003)
+        }
+    }
+
+    impl ConditionallySelectable for Counted {
+        fn conditional_select(a: &Self, b: &Self, choice: Choice) -> Self {
+            Counted(u64::conditional_select(&a.0, &b.0, choice))
+        }
+    }
+
+    fn multiplications<F: FnOnce() -> Counted>(f: F) -> (u64, usize) {
+        MULTIPLICATIONS.with(|m| m.set(0));
+        let res = f().0;
+        (res, MULTIPLICATIONS.with(|m| m.get()))
+    }
+
+    fn naive(base: u64, exp: u64) -> u64 {
+        (0..exp).fold(1, |acc, _| acc * base % 1000003)
+    }
+
+    #[test]
+    fn small_exponents() {
+        for width in 1..9 {
+            for exp in 0..70u64 {
+                assert_eq!(pow_fixed(Counted(3), exp, Counted(1), width).0, naive(3, exp));
+                assert_eq!(pow_fixed_ct(Counted(3), exp, Counted(1), width).0, naive(3, exp));
+                assert_eq!(pow_sliding(Counted(3), exp, Counted(1), width).0, naive(3, exp));
+            }
+        }
+    }
+
+    #[test]
+    fn vartime_skips_leading_zeros() {
+        // 10 = 0b1010: a single window for width 4 after 3 table multiplications,
+        // none of the 60 leading zeros is squared
+        assert_eq!(multiplications(|| pow_sliding(Counted(3), 10u64, Counted(1), 4)), (59049, 9));
+
+        // fixed window spends the same work on any exponent: 15 table entries,
+        // 60 squarings and 15 multiplications for 16 windows
+        assert_eq!(multiplications(|| pow_fixed(Counted(3), 10u64, Counted(1), 4)).1, 90);
+        assert_eq!(multiplications(|| pow_fixed(Counted(3), u64::MAX, Counted(1), 4)).1, 90);
+        assert_eq!(multiplications(|| pow_fixed_ct(Counted(3), u64::MAX, Counted(1), 4)).1, 90);
+    }
+
+    #[test]
+    #[should_panic]
+    fn zero_width() {
+        pow_fixed(Counted(3), 10u64, Counted(1), 0);
+    }
+
+    quickcheck! {
+        fn windows_agree(base: u64, exp: u64, width: u8) -> bool {
+            let width = 1 + width as usize % 8;
+            let base = Counted(base % 1000003);
+            let expected = pow_fixed(base.clone(), exp, Counted(1), 1).0;
+
+            pow_fixed(base.clone(), exp, Counted(1), width).0 == expected &&
+                pow_sliding(base, exp, Counted(1), width).0 == expected
+        }
+    }
+}
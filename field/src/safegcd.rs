@@ -0,0 +1,280 @@
+//! Constant-time modular inversion by Bernstein–Yang safegcd
+//!
+//! Follows "Fast constant-time gcd computation and modular inversion" (Bernstein, Yang, 2019)
+//! with divsteps batched 62 at a time on the low bits, as in libsecp256k1's `modinv64`.
+//! Multi-limb values are kept as signed 62-bit limbs: N limbs of 62 bits and a signed top limb.
+
+const M62: u64 = u64::MAX >> 2;
+
+/// Signed number of N 62-bit limbs and a signed top limb
+#[derive(Clone, Copy)]
+struct Signed62<const N: usize> {
+    low: [i64; N],
+    high: i64,
+}
+
+impl<const N: usize> Signed62<N> {
+    fn get(&self, i: usize) -> i64 {
+        if i < N { self.low[i] } else { self.high }
+    }
+
+    fn set(&mut self, i: usize, v: i64) {
+        if i < N { self.low[i] = v } else { self.high = v }
+    }
+
+    fn from_limbs(limbs: &[u64; N]) -> Self {
+        let mut result = Signed62 { low: [0; N], high: 0 };
+        for i in 0..=N {
+            // bits [62 * i, 62 * i + 62)
+            let (word, shift) = ((62 * i) / 64, (62 * i) % 64);
+            let mut v = if word < N { limbs[word] >> shift } else { 0 };
+            if shift > 2 && word + 1 < N {
+                v |= limbs[word + 1] << (64 - shift);
+            }
+            result.set(i, if i < N { (v & M62) as i64 } else { v as i64 });
+        }
+        result
+    }
+
+    /// Limbs of a value in [0, 2^(64 * N)), normalized beforehand
+    fn into_limbs(self) -> [u64; N] {
+        let mut result = [0u64; N];
+        for i in 0..=N {
+            let v = self.get(i) as u64;
+            let (word, shift) = ((62 * i) / 64, (62 * i) % 64);
+            if word < N {
+                result[word] |= v << shift;
+            }
+            if shift > 2 && word + 1 < N {
+                result[word + 1] |= v >> (64 - shift);
+            }
+        }
+        result
+    }
+
+    /// Sign mask of the value, all ones when negative
+    fn sign(&self) -> i64 {
+        self.high >> 63
+    }
+
+    /// self + (modulus & mask), with limbs renormalized
+    fn add_masked(&mut self, modulus: &Self, mask: i64) {
+        let mut carry = 0i64;
+        for i in 0..=N {
+            let v = self.get(i) + (modulus.get(i) & mask) + carry;
+            if i < N {
+                self.set(i, v & M62 as i64);
+                carry = v >> 62;
+            } else {
+                self.set(i, v);
+            }
+        }
+    }
+
+    /// self negated when mask is all ones, with limbs renormalized
+    fn negate_masked(&mut self, mask: i64) {
+        let mut carry = 0i64;
+        for i in 0..=N {
+            let v = (self.get(i) ^ mask) - mask + carry;
+            if i < N {
+                self.set(i, v & M62 as i64);
+                carry = v >> 62;
+            } else {
+                self.set(i, v);
+            }
+        }
+    }
+}
+
+/// Transition matrix of 62 divsteps, scaled by 2^62
+struct Transition {
+    u: i64,
+    v: i64,
+    q: i64,
+    r: i64,
+}
+
+/// 62 divsteps on the low bits of f and g without secret-dependent branches
+///
+/// divstep(delta, f, g) is (1 - delta, g, (g - f) / 2) when delta > 0 and g is odd,
+/// (1 + delta, f, (g + f) / 2) when g is odd and (1 + delta, f, g / 2) otherwise.
+fn divsteps_62(mut delta: i64, f0: u64, g0: u64) -> (i64, Transition) {
+    let (mut f, mut g) = (f0, g0);
+    let (mut u, mut v, mut q, mut r) = (1u64, 0u64, 0u64, 1u64);
+
+    for _ in 0..62 {
+        let odd = (g & 1).wrapping_neg();
+        // swap when delta > 0 and g is odd
+        let swap = ((delta.wrapping_neg() >> 63) as u64) & odd;
+
+        // (f, g, u, v, q, r, delta) = (g, -f, q, r, -u, -v, -delta) when swapping
+        let (t_f, t_u, t_v) = (f, u, v);
+        f ^= (f ^ g) & swap;
+        u ^= (u ^ q) & swap;
+        v ^= (v ^ r) & swap;
+        g ^= (g ^ t_f) & swap;
+        q ^= (q ^ t_u) & swap;
+        r ^= (r ^ t_v) & swap;
+        g = (g ^ swap).wrapping_sub(swap);
+        q = (q ^ swap).wrapping_sub(swap);
+        r = (r ^ swap).wrapping_sub(swap);
+        delta = (delta ^ swap as i64).wrapping_sub(swap as i64);
+
+        // g is odd exactly when it was odd before the swap
+        g = g.wrapping_add(f & odd);
+        q = q.wrapping_add(u & odd);
+        r = r.wrapping_add(v & odd);
+
+        delta = delta.wrapping_add(1);
+        g >>= 1;
+        u <<= 1;
+        v <<= 1;
+    }
+
+    (delta, Transition { u: u as i64, v: v as i64, q: q as i64, r: r as i64 })
+}
+
+/// (f, g) = (u f + v g, q f + r g) / 2^62, the division is exact
+fn update_fg<const N: usize>(f: &mut Signed62<N>, g: &mut Signed62<N>, t: &Transition) {
+    let (u, v, q, r) = (t.u as i128, t.v as i128, t.q as i128, t.r as i128);
+
+    let mut cf = u * f.get(0) as i128 + v * g.get(0) as i128;
+    let mut cg = q * f.get(0) as i128 + r * g.get(0) as i128;
+    cf >>= 62;
+    cg >>= 62;
+
+    for i in 1..=N {
+        cf += u * f.get(i) as i128 + v * g.get(i) as i128;
+        cg += q * f.get(i) as i128 + r * g.get(i) as i128;
+        f.set(i - 1, (cf as u64 & M62) as i64);
+        g.set(i - 1, (cg as u64 & M62) as i64);
+        cf >>= 62;
+        cg >>= 62;
+    }
+
+    f.set(N, cf as i64);
+    g.set(N, cg as i64);
+}
+
+/// (d, e) = (u d + v e, q d + r e) / 2^62 mod modulus, keeping both within (-2 modulus, modulus)
+fn update_de<const N: usize>(d: &mut Signed62<N>, e: &mut Signed62<N>, t: &Transition, modulus: &Signed62<N>, inv62: u64) {
+    let (sd, se) = (d.sign(), e.sign());
+
+    // multiples of the modulus to add, so that the sums are divisible by 2^62 and stay in range
+    let mut md = (t.u & sd) + (t.v & se);
+    let mut me = (t.q & sd) + (t.r & se);
+
+    let (u, v, q, r) = (t.u as i128, t.v as i128, t.q as i128, t.r as i128);
+    let mut cd = u * d.get(0) as i128 + v * e.get(0) as i128;
+    let mut ce = q * d.get(0) as i128 + r * e.get(0) as i128;
+
+    md -= (inv62.wrapping_mul(cd as u64).wrapping_add(md as u64) & M62) as i64;
+    me -= (inv62.wrapping_mul(ce as u64).wrapping_add(me as u64) & M62) as i64;
+
+    cd += modulus.get(0) as i128 * md as i128;
+    ce += modulus.get(0) as i128 * me as i128;
+    cd >>= 62;
+    ce >>= 62;
+
+    for i in 1..=N {
+        cd += u * d.get(i) as i128 + v * e.get(i) as i128 + modulus.get(i) as i128 * md as i128;
+        ce += q * d.get(i) as i128 + r * e.get(i) as i128 + modulus.get(i) as i128 * me as i128;
+        d.set(i - 1, (cd as u64 & M62) as i64);
+        e.set(i - 1, (ce as u64 & M62) as i64);
+        cd >>= 62;
+        ce >>= 62;
+    }
+
+    d.set(N, cd as i64);
+    e.set(N, ce as i64);
+}
+
+/// Number of divsteps sufficient for moduli of the given bit length (Bernstein, Yang, theorem 11.2)
+fn divsteps_bound(bits: usize) -> usize {
+    if bits < 46 { (49 * bits + 80) / 17 } else { (49 * bits + 57) / 17 }
+}
+
+/// value^-1 mod modulus by safegcd, zero is mapped to zero
+///
+/// The modulus should be odd and the value less than it. The sequence of operations only
+/// depends on the bit length of the modulus, not on the value.
+pub fn safegcd_inverse<const N: usize>(value: [u64; N], modulus: [u64; N]) -> [u64; N] {
+    let bits = (0..64 * N).rev().find(|&i| (modulus[i / 64] >> (i % 64)) & 1 == 1).map_or(0, |i| i + 1);
+
+    // modulus^-1 mod 2^62 by Newton iteration
+    let mut inv = 1u64;
+    for _ in 0..6 {
+        inv = inv.wrapping_mul(2u64.wrapping_sub(modulus[0].wrapping_mul(inv)));
+    }
+    let inv62 = inv & M62;
+
+    let m = Signed62::from_limbs(&modulus);
+    let (mut f, mut g) = (m, Signed62::from_limbs(&value));
+    let mut d = Signed62 { low: [0; N], high: 0 };
+    let mut e = d;
+    e.set(0, 1);
+
+    let mut delta = 1;
+    for _ in 0..divsteps_bound(bits).div_ceil(62) {
+        let (next, t) = divsteps_62(delta, f.get(0) as u64, g.get(0) as u64);
+        delta = next;
+        update_de(&mut d, &mut e, &t, &m, inv62);
+        update_fg(&mut f, &mut g, &t);
+    }
+
+    // f = ±1 now, value^-1 = d * f; d is within (-2 modulus, modulus)
+    d.add_masked(&m, d.sign());
+    d.negate_masked(f.sign());
+    d.add_masked(&m, d.sign());
+
+    d.into_limbs()
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::{safegcd_inverse, Signed62};
+    use arith::{ModInv, ModInvCt};
+    use field::Field;
+    use test::Mod1125899839733759Field;
+
+    #[test]
+    fn limbs_roundtrip() {
+        let limbs = [0x0123456789abcdef, 0xfedcba9876543210, 0xffffffffffffffff, 0x8000000000000001];
+        assert_eq!(Signed62::from_limbs(&limbs).into_limbs(), limbs);
+        assert_eq!(Signed62::from_limbs(&[u64::MAX]).into_limbs(), [u64::MAX]);
+    }
+
+    #[test]
+    fn small() {
+        assert_eq!(safegcd_inverse([6], [19]), [16]);
+        assert_eq!(safegcd_inverse([0], [19]), [0]);
+        assert_eq!(safegcd_inverse([1], [19]), [1]);
+        assert_eq!(safegcd_inverse([18], [19]), [18]);
+        // 2^64 - 59
+        assert_eq!(safegcd_inverse([2], [18446744073709551557]), [9223372036854775779]);
+    }
+
+    quickcheck! {
+        fn single_limb_equals_euclid(x: u64, p: u64) -> bool {
+            let p = p | 1;
+            if p == 1 {
+                return true;
+            }
+
+            let x = x % p;
+            let expected = x.inv(p);
+
+            // non-coprime values have no inverse, Euclid result is meaningless there
+            (x as u128 * expected as u128 % p as u128 != 1) || safegcd_inverse([x], [p]) == [expected]
+        }
+
+        fn field_inverse_equals_euclid(x: u64) -> bool {
+            let p = Mod1125899839733759Field::MODULUS;
+
+            let x = x % p;
+
+            x.inv_ct(p) == x.inv(p)
+        }
+    }
+}
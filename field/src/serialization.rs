@@ -0,0 +1,215 @@
+//! Serde support: hex strings in human-readable formats, fixed-width bytes otherwise
+
+use std::fmt;
+
+use serde::{Serialize, Serializer, Deserialize, Deserializer};
+use serde::de::{self, Visitor, SeqAccess};
+
+use {field, FieldElement, MontgomeryElement, BarrettElement};
+use arith::Scalar;
+
+const HEX: &[u8; 16] = b"0123456789abcdef";
+
+fn to_hex(bytes: &[u8]) -> String {
+    let mut result = String::with_capacity(bytes.len() * 2);
+    for b in bytes {
+        result.push(HEX[(b >> 4) as usize] as char);
+        result.push(HEX[(b & 0xf) as usize] as char);
+    }
+    result
+}
+
+fn from_hex(s: &str) -> Option<Vec<u8>> {
+    fn digit(c: u8) -> Option<u8> {
+        (c as char).to_digit(16).map(|d| d as u8)
+    }
+
+    if s.len() % 2 != 0 {
+        return None;
+    }
+
+    s.as_bytes()
+        .chunks(2)
+        .map(|pair| Some(digit(pair[0])? << 4 | digit(pair[1])?))
+        .collect()
+}
+
+/// Serialize fixed-width bytes as a lowercase hex string in human-readable formats, as bytes otherwise
+pub fn serialize_fixed_bytes<S: Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+    if serializer.is_human_readable() {
+        serializer.serialize_str(&to_hex(bytes))
+    } else {
+        serializer.serialize_bytes(bytes)
+    }
+}
+
+struct FixedBytesVisitor {
+    len: usize,
+}
+
+impl FixedBytesVisitor {
+    fn check<E: de::Error>(&self, bytes: Vec<u8>) -> Result<Vec<u8>, E> {
+        if bytes.len() != self.len {
+            return Err(E::invalid_length(bytes.len(), self));
+        }
+        Ok(bytes)
+    }
+}
+
+impl<'de> Visitor<'de> for FixedBytesVisitor {
+    type Value = Vec<u8>;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} bytes or a hex string of {} digits", self.len, self.len * 2)
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Vec<u8>, E> {
+        let bytes = from_hex(v).ok_or_else(|| E::invalid_value(de::Unexpected::Str(v), &self))?;
+        self.check(bytes)
+    }
+
+    fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<Vec<u8>, E> {
+        self.check(v.to_vec())
+    }
+
+    fn visit_byte_buf<E: de::Error>(self, v: Vec<u8>) -> Result<Vec<u8>, E> {
+        self.check(v)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Vec<u8>, A::Error> {
+        let mut bytes = Vec::with_capacity(self.len);
+        while let Some(b) = seq.next_element()? {
+            // stop at the first extra element, the sequence may be arbitrarily long
+            if bytes.len() == self.len {
+                return Err(de::Error::invalid_length(self.len + 1, &self));
+            }
+            bytes.push(b);
+        }
+        self.check(bytes)
+    }
+}
+
+/// Deserialize exactly `len` bytes written by [`serialize_fixed_bytes`](fn.serialize_fixed_bytes.html)
+pub fn deserialize_fixed_bytes<'de, D: Deserializer<'de>>(deserializer: D, len: usize) -> Result<Vec<u8>, D::Error> {
+    if deserializer.is_human_readable() {
+        deserializer.deserialize_str(FixedBytesVisitor { len })
+    } else {
+        deserializer.deserialize_bytes(FixedBytesVisitor { len })
+    }
+}
+
+impl<F: field::Field> Serialize for FieldElement<F> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_fixed_bytes(&self.to_bytes_be(), serializer)
+    }
+}
+
+impl<'de, F: field::Field> Deserialize<'de> for FieldElement<F> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let bytes = deserialize_fixed_bytes(deserializer, F::Value::max_bits() / 8)?;
+        FieldElement::from_bytes_be(&bytes).map_err(de::Error::custom)
+    }
+}
+
+impl<F: field::MontgomeryField> Serialize for MontgomeryElement<F> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_fixed_bytes(&self.to_bytes_be(), serializer)
+    }
+}
+
+impl<'de, F: field::MontgomeryField> Deserialize<'de> for MontgomeryElement<F> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let bytes = deserialize_fixed_bytes(deserializer, F::Value::max_bits() / 8)?;
+        MontgomeryElement::from_bytes_be(&bytes).map_err(de::Error::custom)
+    }
+}
+
+impl<F: field::BarrettField> Serialize for BarrettElement<F> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_fixed_bytes(&self.to_bytes_be(), serializer)
+    }
+}
+
+impl<'de, F: field::BarrettField> Deserialize<'de> for BarrettElement<F> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let bytes = deserialize_fixed_bytes(deserializer, F::Value::max_bits() / 8)?;
+        BarrettElement::from_bytes_be(&bytes).map_err(de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    extern crate serde_json;
+    extern crate ciborium;
+
+    use {FieldElement, MontgomeryElement};
+    use test::Mod1125899839733759Field;
+
+    type Elem = FieldElement<Mod1125899839733759Field>;
+    type MontElem = MontgomeryElement<Mod1125899839733759Field>;
+
+    fn cbor<T: ::serde::Serialize>(value: &T) -> Vec<u8> {
+        let mut buf = Vec::new();
+        ciborium::ser::into_writer(value, &mut buf).unwrap();
+        buf
+    }
+
+    #[test]
+    fn json() {
+        let elem: Elem = 0x1234.into();
+        assert_eq!(serde_json::to_string(&elem).unwrap(), "\"0000000000001234\"");
+        assert_eq!(serde_json::from_str::<Elem>("\"0000000000001234\"").unwrap(), elem);
+
+        let mont: MontElem = 0x1234.into();
+        assert_eq!(serde_json::to_string(&mont).unwrap(), "\"0000000000001234\"");
+        assert_eq!(serde_json::from_str::<MontElem>("\"0000000000001234\"").unwrap(), mont);
+
+        // modulus itself, wrong length and bad digits are rejected
+        assert!(serde_json::from_str::<Elem>("\"0003fffffbffffff\"").is_err());
+        assert!(serde_json::from_str::<Elem>("\"1234\"").is_err());
+        assert!(serde_json::from_str::<Elem>("\"000000000000123x\"").is_err());
+        assert!(serde_json::from_str::<MontElem>("\"0003fffffbffffff\"").is_err());
+    }
+
+    #[test]
+    fn cbor_bytes() {
+        let elem: Elem = 0x1234.into();
+        let encoded = cbor(&elem);
+
+        // byte string of length 8
+        assert_eq!(encoded, vec![0x48, 0, 0, 0, 0, 0, 0, 0x12, 0x34]);
+        assert_eq!(ciborium::de::from_reader::<Elem, _>(&encoded[..]).unwrap(), elem);
+
+        let mut modulus = vec![0x48];
+        modulus.extend_from_slice(&1125899839733759u64.to_be_bytes());
+        assert!(ciborium::de::from_reader::<Elem, _>(&modulus[..]).is_err());
+    }
+
+    #[test]
+    fn sequences() {
+        use serde::de::value::{Error, SeqDeserializer};
+        use super::deserialize_fixed_bytes;
+
+        let exact = SeqDeserializer::<_, Error>::new(vec![7u8; 8].into_iter());
+        assert_eq!(deserialize_fixed_bytes(exact, 8).unwrap(), vec![7; 8]);
+
+        let short = SeqDeserializer::<_, Error>::new(vec![7u8; 7].into_iter());
+        assert!(deserialize_fixed_bytes(short, 8).is_err());
+
+        // rejected after 9 elements instead of being read to the end
+        let endless = SeqDeserializer::<_, Error>::new(::std::iter::repeat(7u8));
+        assert!(deserialize_fixed_bytes(endless, 8).is_err());
+    }
+
+    quickcheck! {
+        fn roundtrip(x: u64) -> bool {
+            let (elem, mont): (Elem, MontElem) = (x.into(), x.into());
+
+            serde_json::from_str::<Elem>(&serde_json::to_string(&elem).unwrap()).unwrap() == elem &&
+                serde_json::from_str::<MontElem>(&serde_json::to_string(&mont).unwrap()).unwrap() == mont &&
+                ciborium::de::from_reader::<Elem, _>(&cbor(&elem)[..]).unwrap() == elem &&
+                ciborium::de::from_reader::<MontElem, _>(&cbor(&mont)[..]).unwrap() == mont
+        }
+    }
+}
@@ -0,0 +1,222 @@
+//! Square roots in prime fields
+
+use std::ops::{Mul, Neg};
+
+use arith::{Scalar, ModMul};
+use power;
+
+/// Arithmetic needed by the generic prime field algorithms
+pub(crate) trait PrimeFieldOps:
+    Clone +
+    PartialEq +
+    Mul<Output=Self> +
+    Mul<u32, Output=Self> +
+    Neg<Output=Self>
+{}
+
+impl<T> PrimeFieldOps for T
+    where T: Clone + PartialEq + Mul<Output=T> + Mul<u32, Output=T> + Neg<Output=T>
+{}
+
+/// Additive identity of the same field as `one`
+#[allow(clippy::erasing_op)]
+fn zero_like<T: PrimeFieldOps>(one: &T) -> T {
+    one.clone() * 0
+}
+
+/// Exact division of `value` by 2^k
+///
+/// `value` should be divisible by 2^k and less than `module`, so the modular
+/// multiplication by inverse of 2^k gives the same result as the integer division.
+fn div_pow2<V: Scalar>(value: V, k: usize, module: V) -> V {
+    let mut pow2 = V::one();
+    for _ in 0..k {
+        pow2 = pow2.add(pow2, module);
+    }
+
+    ModMul::<V>::mul(value, pow2.inv(module), module)
+}
+
+/// base^exp for exponents derived from the public modulus, so the variable time is fine
+fn pow<T: PrimeFieldOps, V: Scalar>(base: T, exp: V, one: T) -> T {
+    power::pow_sliding(base, exp, one, power::DEFAULT_WINDOW)
+}
+
+/// Legendre symbol of `value` in the prime field of order `module` by Euler's criterion
+pub(crate) fn legendre<T: PrimeFieldOps, V: Scalar>(value: T, one: T, module: V) -> i8 {
+    if value == zero_like(&one) {
+        return 0;
+    }
+
+    // a^((p-1)/2) is either 1 or -1
+    let p_minus_one = V::one().neg(module);
+    if pow(value, div_pow2(p_minus_one, 1, module), one.clone()) == one { 1 } else { -1 }
+}
+
+/// Square root of `value` in the prime field of order `module`
+///
+/// Uses a^((p+1)/4) when p = 3 mod 4 and Tonelli–Shanks otherwise.
+pub(crate) fn sqrt<T: PrimeFieldOps, V: Scalar>(value: T, one: T, module: V) -> Option<T> {
+    if value == zero_like(&one) {
+        return Some(value);
+    }
+
+    let one_v = V::one();
+
+    if module.bit(0) && module.bit(1) {
+        // p = 3 mod 4, (p + 1) / 4 = (p - 3) / 4 + 1
+        let three = one_v.add(one_v, module).add(one_v, module);
+        let exp = div_pow2(three.neg(module), 2, module).add(one_v, module);
+
+        let root = pow(value.clone(), exp, one);
+        return if root.clone() * root.clone() == value { Some(root) } else { None };
+    }
+
+    // p - 1 = 2^s * q, q is odd
+    let p_minus_one = one_v.neg(module);
+    let mut s = 0;
+    while !p_minus_one.bit(s) {
+        s += 1;
+    }
+    let q = div_pow2(p_minus_one, s, module);
+
+    // any quadratic non-residue z
+    let mut z = 2;
+    let mut non_residue = one.clone() * z;
+    while legendre(non_residue.clone(), one.clone(), module) != -1 {
+        z += 1;
+        non_residue = one.clone() * z;
+    }
+
+    let mut m = s;
+    let mut c = pow(non_residue, q, one.clone());
+    let mut t = pow(value.clone(), q, one.clone());
+    let mut r = pow(value, div_pow2(q.add(one_v, module), 1, module), one.clone());
+
+    while t != one {
+        // least i such that t^(2^i) = 1
+        let mut i = 0;
+        let mut t2i = t.clone();
+        while t2i != one {
+            t2i = t2i.clone() * t2i;
+            i += 1;
+            if i == m {
+                return None;
+            }
+        }
+
+        let mut b = c;
+        for _ in 0..m - i - 1 {
+            b = b.clone() * b;
+        }
+
+        m = i;
+        c = b.clone() * b.clone();
+        t = t * c.clone();
+        r = r * b;
+    }
+
+    Some(r)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use {FieldElement, MontgomeryElement, FieldValue};
+    use test::{Mod19Field, Mod998244353Field, Mod1125899839733759Field};
+
+    #[test]
+    fn smoky() {
+        let elem: FieldElement<Mod19Field> = 5.into();
+        let root = elem.sqrt().unwrap();
+        assert!(root == 9.into() || root == 10.into());
+
+        let elem: FieldElement<Mod19Field> = 2.into();
+        assert_eq!(elem.sqrt(), None);
+
+        assert_eq!(FieldElement::<Mod19Field>::zero().sqrt(), Some(0.into()));
+    }
+
+    #[test]
+    fn tonelli_shanks() {
+        // 998244353 = 119 * 2^23 + 1
+        let elem: FieldElement<Mod998244353Field> = 4.into();
+        let root = elem.sqrt().unwrap();
+        assert!(root == 2.into() || root == -FieldElement::from(2));
+
+        // 3 is a generator of the multiplicative group, hence non-residue
+        let elem: MontgomeryElement<Mod998244353Field> = 3.into();
+        assert_eq!(elem.sqrt(), None);
+    }
+
+    #[test]
+    fn legendre() {
+        let residues = [1, 4, 5, 6, 7, 9, 11, 16, 17];
+
+        for x in 1..19u64 {
+            let expected = if residues.contains(&x) { 1 } else { -1 };
+            assert_eq!(FieldElement::<Mod19Field>::from(x).legendre(), expected);
+            assert_eq!(MontgomeryElement::<Mod19Field>::from(x).legendre(), expected);
+        }
+
+        assert_eq!(FieldElement::<Mod19Field>::zero().legendre(), 0);
+        assert!(FieldElement::<Mod19Field>::zero().is_square());
+        assert!(!FieldElement::<Mod19Field>::from(2).is_square());
+    }
+
+    #[test]
+    fn jacobi() {
+        use arith::Jacobi;
+
+        // composite modules: (2 / 15) = 1, (7 / 15) = -1, (5 / 15) = 0
+        assert_eq!(2u64.jacobi(15), 1);
+        assert_eq!(7u64.jacobi(15), -1);
+        assert_eq!(5u64.jacobi(15), 0);
+        assert_eq!(1001u64.jacobi(9907), -1);
+    }
+
+    quickcheck! {
+        fn jacobi_equals_legendre(x: u64) -> bool {
+            use arith::Jacobi;
+
+            let x_e: FieldElement<Mod998244353Field> = x.into();
+            x.jacobi(998244353) == x_e.legendre()
+        }
+
+        fn square_is_square(x: u64) -> bool {
+            let x_e: MontgomeryElement<Mod1125899839733759Field> = x.into();
+            x_e.squared().legendre() == if x % 1125899839733759 == 0 { 0 } else { 1 }
+        }
+
+        fn legendre_agrees_with_sqrt(x: u64) -> bool {
+            let x_e: FieldElement<Mod998244353Field> = x.into();
+            x_e.is_square() == x_e.sqrt().is_some()
+        }
+
+        fn sqrt_of_square(x: u64) -> bool {
+            let x_e: FieldElement<Mod1125899839733759Field> = x.into();
+            let root = x_e.squared().sqrt().unwrap();
+            root == x_e || root == -x_e
+        }
+
+        fn sqrt_of_square_tonelli_shanks(x: u64) -> bool {
+            let x_e: FieldElement<Mod998244353Field> = x.into();
+            let root = x_e.squared().sqrt().unwrap();
+            root == x_e || root == -x_e
+        }
+
+        fn sqrt_of_square_montgomery(x: u64) -> bool {
+            let x_e: MontgomeryElement<Mod998244353Field> = x.into();
+            let root = x_e.squared().sqrt().unwrap();
+            root == x_e || root == -x_e
+        }
+
+        fn sqrt_squared_is_self(x: u64) -> bool {
+            let x_e: MontgomeryElement<Mod998244353Field> = x.into();
+            match x_e.sqrt() {
+                Some(root) => root.squared() == x_e,
+                None => true,
+            }
+        }
+    }
+}
@@ -1,5 +1,6 @@
 
-use field;
+use field::{self, Field};
+use {montgomery_r, montgomery_r_inverse, montgomery_inv, barrett_mu};
 
 #[derive(Debug, PartialEq, Clone, Copy)]
 pub struct Mod19Field;
@@ -7,8 +8,16 @@ pub struct Mod19Field;
 impl field::Field for Mod19Field {
     type Value = u64;
     const MODULUS: Self::Value = 19;
-    const R: Self::Value = 16;
-    const R_INVERSE: Self::Value = 6;
+}
+
+impl field::MontgomeryField for Mod19Field {
+    const R: Self::Value = montgomery_r([Self::MODULUS])[0];
+    const R_INVERSE: Self::Value = montgomery_r_inverse([Self::MODULUS])[0];
+    const INV: u64 = montgomery_inv([Self::MODULUS]);
+}
+
+impl field::BarrettField for Mod19Field {
+    const MU: Self::Value = barrett_mu([Self::MODULUS])[0];
 }
 
 #[derive(Clone, Copy, PartialEq, Debug)]
@@ -18,6 +27,42 @@ impl field::Field for Mod1125899839733759Field {
     type Value = u64;
 
     const MODULUS: u64 = 1125899839733759;
-    const R: u64 = 1099511644160;
-    const R_INVERSE: u64 = 343597359104;
-}
\ No newline at end of file
+}
+
+impl field::MontgomeryField for Mod1125899839733759Field {
+    const R: u64 = montgomery_r([Self::MODULUS])[0];
+    const R_INVERSE: u64 = montgomery_r_inverse([Self::MODULUS])[0];
+    const INV: u64 = montgomery_inv([Self::MODULUS]);
+}
+
+impl field::BarrettField for Mod1125899839733759Field {
+    const MU: u64 = barrett_mu([Self::MODULUS])[0];
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Mod998244353Field;
+
+impl field::Field for Mod998244353Field {
+    type Value = u64;
+
+    const MODULUS: u64 = 998244353;
+}
+
+impl field::MontgomeryField for Mod998244353Field {
+    const R: u64 = montgomery_r([Self::MODULUS])[0];
+    const R_INVERSE: u64 = montgomery_r_inverse([Self::MODULUS])[0];
+    const INV: u64 = montgomery_inv([Self::MODULUS]);
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Mod18446744073709551557Field;
+
+impl field::Field for Mod18446744073709551557Field {
+    type Value = u64;
+
+    const MODULUS: u64 = 18446744073709551557;
+}
+
+impl field::BarrettField for Mod18446744073709551557Field {
+    const MU: u64 = barrett_mu([Self::MODULUS])[0];
+}
@@ -0,0 +1,169 @@
+//! Parsing and formatting of scalar values as decimal and hex strings
+
+use std::{error, fmt};
+
+use arith::Scalar;
+
+/// String parsing error
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ParseError {
+    /// Input has no digits
+    Empty,
+    /// Input contains a character that is not a digit of its radix
+    InvalidDigit,
+    /// Value does not fit the value type
+    Overflow,
+    /// Value is not less than the field modulus
+    NotCanonical,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ParseError::Empty => write!(f, "cannot parse number from empty string"),
+            ParseError::InvalidDigit => write!(f, "invalid digit found in string"),
+            ParseError::Overflow => write!(f, "number too large to fit in the value type"),
+            ParseError::NotCanonical => write!(f, "number is not less than the field modulus"),
+        }
+    }
+}
+
+impl error::Error for ParseError {}
+
+/// Parse decimal or `0x`-prefixed hex string
+pub(crate) fn parse<V: Scalar>(s: &str) -> Result<V, ParseError> {
+    let (digits, radix) = match s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")) {
+        Some(hex) => (hex, 16),
+        None => (s, 10),
+    };
+
+    if digits.is_empty() {
+        return Err(ParseError::Empty);
+    }
+
+    // big-endian bytes, bytes = bytes * radix + digit for every digit
+    let mut bytes = vec![0u8; V::max_bits() / 8];
+    for c in digits.chars() {
+        let mut carry = c.to_digit(radix).ok_or(ParseError::InvalidDigit)?;
+        for b in bytes.iter_mut().rev() {
+            let t = (*b as u32) * radix + carry;
+            *b = t as u8;
+            carry = t >> 8;
+        }
+        if carry != 0 {
+            return Err(ParseError::Overflow);
+        }
+    }
+
+    Ok(V::read_be_bytes(&bytes))
+}
+
+/// Parse string, rejecting values not less than the modulus
+pub(crate) fn parse_canonical<V: Scalar>(s: &str, modulus: V) -> Result<V, ParseError> {
+    let value = parse::<V>(s)?;
+    if value % modulus != value {
+        return Err(ParseError::NotCanonical);
+    }
+    Ok(value)
+}
+
+fn be_bytes<V: Scalar>(value: V) -> Vec<u8> {
+    let mut bytes = vec![0u8; V::max_bits() / 8];
+    value.write_be_bytes(&mut bytes);
+    bytes
+}
+
+/// Format value as decimal, respecting width, fill and sign flags
+pub(crate) fn fmt_decimal<V: Scalar>(value: V, f: &mut fmt::Formatter) -> fmt::Result {
+    let mut bytes = be_bytes(value);
+    let mut digits = Vec::new();
+
+    // long division by 10 until nothing is left
+    loop {
+        let mut remainder = 0u32;
+        for b in bytes.iter_mut() {
+            let t = (remainder << 8) | *b as u32;
+            *b = (t / 10) as u8;
+            remainder = t % 10;
+        }
+        digits.push(b'0' + remainder as u8);
+
+        if bytes.iter().all(|&b| b == 0) {
+            break;
+        }
+    }
+    digits.reverse();
+
+    f.pad_integral(true, "", ::std::str::from_utf8(&digits).expect("digits are ascii"))
+}
+
+/// Format value as lowercase hex without leading zeros, `{:#x}` adds `0x`
+pub(crate) fn fmt_lower_hex<V: Scalar>(value: V, f: &mut fmt::Formatter) -> fmt::Result {
+    let mut hex = String::new();
+    for b in be_bytes(value) {
+        hex.push_str(&format!("{:02x}", b));
+    }
+
+    let digits = hex.trim_start_matches('0');
+    f.pad_integral(true, "0x", if digits.is_empty() { "0" } else { digits })
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::{parse, parse_canonical, ParseError};
+
+    struct Decimal(u64);
+
+    impl ::std::fmt::Display for Decimal {
+        fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+            super::fmt_decimal(self.0, f)
+        }
+    }
+
+    struct Hex(u64);
+
+    impl ::std::fmt::LowerHex for Hex {
+        fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+            super::fmt_lower_hex(self.0, f)
+        }
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert_eq!(parse::<u64>("12345"), Ok(12345));
+        assert_eq!(parse::<u64>("0x1aF"), Ok(0x1af));
+        assert_eq!(parse::<u64>("0X10"), Ok(16));
+        assert_eq!(parse::<u64>("18446744073709551615"), Ok(u64::MAX));
+        assert_eq!(parse::<u64>("0xffffffffffffffff"), Ok(u64::MAX));
+
+        assert_eq!(parse::<u64>(""), Err(ParseError::Empty));
+        assert_eq!(parse::<u64>("0x"), Err(ParseError::Empty));
+        assert_eq!(parse::<u64>("12a"), Err(ParseError::InvalidDigit));
+        assert_eq!(parse::<u64>("-1"), Err(ParseError::InvalidDigit));
+        assert_eq!(parse::<u64>("0x12g"), Err(ParseError::InvalidDigit));
+        assert_eq!(parse::<u64>("18446744073709551616"), Err(ParseError::Overflow));
+        assert_eq!(parse::<u64>("0x10000000000000000"), Err(ParseError::Overflow));
+
+        assert_eq!(parse_canonical::<u64>("18", 19), Ok(18));
+        assert_eq!(parse_canonical::<u64>("19", 19), Err(ParseError::NotCanonical));
+    }
+
+    #[test]
+    fn format() {
+        assert_eq!(format!("{}", Decimal(0)), "0");
+        assert_eq!(format!("{:>5}", Decimal(42)), "   42");
+        assert_eq!(format!("{:x}", Hex(0)), "0");
+        assert_eq!(format!("{:#x}", Hex(0xabc)), "0xabc");
+        assert_eq!(format!("{:08x}", Hex(0xabc)), "00000abc");
+    }
+
+    quickcheck! {
+        fn matches_std(x: u64) -> bool {
+            format!("{}", Decimal(x)) == format!("{}", x) &&
+                format!("{:x}", Hex(x)) == format!("{:x}", x) &&
+                parse::<u64>(&format!("{}", x)) == Ok(x) &&
+                parse::<u64>(&format!("{:#x}", x)) == Ok(x)
+        }
+    }
+}
@@ -25,8 +25,6 @@ use std::ops::{Mul, Add};
 ///     type Value = u64;
 ///
 ///     const MODULUS: u64 = 1125899839733759;
-///     const R: u64 = 1099511644160;
-///     const R_INVERSE: u64 = 343597359104;
 /// }
 ///
 /// let polynomial = Polynomial::<FieldElement<Mod1125899839733759Field>>::new(vec![1]);
@@ -57,27 +55,46 @@ impl<T: field::FieldValue> Polynomial<T> {
     }
 
     /// Lagrange interpolation
+    ///
+    /// # Panics
+    ///
+    /// Panics if two points share the x coordinate, see [`checked_interpolate`](#method.checked_interpolate).
     pub fn interpolate<I: Into<T>+Copy>(points: &[(I, I)]) -> Self
     {
-        let mut additive_members = Vec::new();
+        Self::checked_interpolate(points).expect("interpolation points should have distinct x coordinates")
+    }
+
+    /// Lagrange interpolation, `None` if two points share the x coordinate
+    pub fn checked_interpolate<I: Into<T>+Copy>(points: &[(I, I)]) -> Option<Self>
+    {
+        let mut numerators = Vec::with_capacity(points.len());
+        let mut denominators = Vec::with_capacity(points.len());
 
         for i in 0..points.len() {
             let mut poly = Self::one();
-            for j in 0..points.len() {
+            for (j, point) in points.iter().enumerate() {
                 if i == j { continue; }
 
                 // (x - x[i]) member
-                poly = poly * Self::new(vec![-points[j].0.into(), T::one()])
+                poly = poly * Self::new(vec![-point.0.into(), T::one()])
             }
 
-            let val = poly.eval(points[i].0.into());
-
-            poly = poly * (T::one() / val) * points[i].1.into();
+            denominators.push(poly.eval(points[i].0.into()));
+            numerators.push(poly);
+        }
 
-            additive_members.push(poly);
+        // denominator is the product of x[i] - x[j], zero when coordinates repeat
+        if !field::batch_invert(&mut denominators).is_empty() {
+            return None;
         }
 
-        additive_members.into_iter().fold(Self::zero(), |acc, p| acc + p)
+        let result = numerators.into_iter()
+            .zip(denominators)
+            .zip(points)
+            .map(|((poly, inv), point)| poly * inv * point.1.into())
+            .fold(Self::zero(), |acc, p| acc + p);
+
+        Some(result)
     }
 
     /// Evaluate polynomial on t
@@ -87,8 +104,8 @@ impl<T: field::FieldValue> Polynomial<T> {
         let mut power = T::one();
 
         for c in self.coefs.iter() {
-            result = result + *c * power;
-            power = power * t.into();
+            result += *c * power;
+            power *= t.into();
         }
 
         result
@@ -110,13 +127,9 @@ impl<T: field::FieldValue> Mul for Polynomial<T>
         let mut result = Vec::with_capacity(order*2);
         result.resize(order*2, T::zero());
 
-        for i in 0..order {
-            for j in 0..order {
-                let c_order = i + j;
-
-                result[c_order] = result[c_order] +
-                    self.coefs.get(i).unwrap_or(&T::zero()).clone() *
-                    other.coefs.get(j).unwrap_or(&T::zero()).clone();
+        for (i, a) in self.coefs.iter().enumerate() {
+            for (j, b) in other.coefs.iter().enumerate() {
+                result[i + j] += *a * b;
             }
         }
 
@@ -131,7 +144,7 @@ impl<T: field::FieldValue> Mul<T> for Polynomial<T>
     fn mul(self, other: T) -> Self {
         let mut coefs = self.coefs;
 
-        for coef in coefs.iter_mut() { *coef = *coef * other }
+        for coef in coefs.iter_mut() { *coef *= other }
 
         Self::new(coefs)
     }
@@ -144,13 +157,11 @@ impl<T: field::FieldValue> Add for Polynomial<T>
     fn add(self, other: Self) -> Self {
         let order = self.coefs.len().max(other.coefs.len());
 
-        let mut result = Vec::with_capacity(order);
+        let mut result = self.coefs;
         result.resize(order, T::zero());
 
-        for i in 0..order {
-            result[i] =
-                self.coefs.get(i).unwrap_or(&T::zero()).clone() +
-                other.coefs.get(i).unwrap_or(&T::zero()).clone();
+        for (r, c) in result.iter_mut().zip(other.coefs.iter()) {
+            *r += c;
         }
 
         Self::new(result)
@@ -171,8 +182,6 @@ mod tests {
         type Value = u64;
 
         const MODULUS: u64 = 1125899839733759;
-        const R: u64 = 1099511644160;
-        const R_INVERSE: u64 = 343597359104;
     }
 
     type TestPolynomial = Polynomial<field::FieldElement<Mod1125899839733759Field>>;
@@ -235,6 +244,32 @@ mod tests {
         ]);
         assert_eq!(p.eval(13), 5.into());
         assert_eq!(p.eval(7), 2.into());
+
+        assert_eq!(TestPolynomial::checked_interpolate(&[(13, 5), (7, 2), (13, 3)]), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "distinct x coordinates")]
+    fn interpolation_repeated_x() {
+        TestPolynomial::interpolate(&[(13, 5), (13, 5)]);
+    }
+
+    #[derive(Clone, Copy, PartialEq, Debug)]
+    pub struct AesField;
+
+    impl field::BinaryField for AesField {
+        const DEGREE: usize = 8;
+        const REDUCTION: &'static [usize] = &[4, 3, 1, 0];
+    }
+
+    #[test]
+    fn interpolation_binary() {
+        type Gf256Polynomial = Polynomial<field::BinaryFieldElement<AesField>>;
+
+        let p = Gf256Polynomial::interpolate(&[(1, 0x57), (2, 0x83), (3, 0x13)]);
+        assert_eq!(p.eval(1u64), 0x57.into());
+        assert_eq!(p.eval(2u64), 0x83.into());
+        assert_eq!(p.eval(3u64), 0x13.into());
     }
 
     quickcheck! {
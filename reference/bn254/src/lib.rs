@@ -0,0 +1,212 @@
+extern crate ng_field as field;
+extern crate ng_uint as uint;
+extern crate ng_curve as curve;
+
+use uint::U256;
+use field::{Field, MontgomeryElement, FieldValue, Fp2, Fp6, Fp12, MinusOne, NonResidue, FrobeniusTable};
+use curve::{Curve, AffinePoint};
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Bn254Field;
+
+impl field::Field for Bn254Field {
+    type Value = U256;
+
+    // 21888242871839275222246405745257275088696311157297823662689037894645226208583
+    const MODULUS: U256 = U256::from_raw(
+        [
+            0x3C208C16D87CFD47,
+            0x97816A916871CA8D,
+            0xB85045B68181585D,
+            0x30644E72E131A029,
+        ]
+    );
+}
+
+impl field::MontgomeryField for Bn254Field {
+    const R: U256 = U256::from_raw(field::montgomery_r(Self::MODULUS.0));
+    const R_INVERSE: U256 = U256::from_raw(field::montgomery_r_inverse(Self::MODULUS.0));
+    const INV: u64 = field::montgomery_inv(Self::MODULUS.0);
+}
+
+impl Bn254Field {
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(v: &'static str) -> MontgomeryElement<Self> {
+        U256::from(v).into()
+    }
+
+    pub fn from_u64(x: u64) -> MontgomeryElement<Self> {
+        U256::from(x).into()
+    }
+}
+
+/// Fp2 = Fp[u] / (u^2 + 1)
+pub type Fq2 = Fp2<MontgomeryElement<Bn254Field>, MinusOne>;
+
+/// Fp6 non-residue, 9 + u
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Xi;
+
+impl NonResidue<Fq2> for Xi {
+    fn non_residue() -> Fq2 {
+        Fp2::new(Bn254Field::from_u64(9), Bn254Field::from_u64(1))
+    }
+
+    fn frobenius_coefficient(degree: u32, power: usize) -> Fq2 {
+        static TABLE: FrobeniusTable<Fq2> = FrobeniusTable::new();
+        TABLE.get::<Self>(degree, power)
+    }
+}
+
+/// Fp6 = Fp2[v] / (v^3 - (9 + u))
+pub type Fq6 = Fp6<Fq2, Xi>;
+
+/// Fp12 = Fp6[w] / (w^2 - v)
+pub type Fq12 = Fp12<Fq2, Xi>;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Bn254Curve;
+
+/// y^2 = x^3 + 3
+impl Curve for Bn254Curve {
+    type Value = MontgomeryElement<Bn254Field>;
+
+    fn generator() -> AffinePoint<Self> {
+        (Bn254Field::from_u64(1), Bn254Field::from_u64(2)).into()
+    }
+
+    fn a() -> Self::Value { Self::Value::zero() }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::{Bn254Curve, Bn254Field, Fq2, Fq6, Fq12, Xi};
+    use curve::Curve;
+    use field::{FieldValue, Fp2, Fp6, NonResidue};
+
+    fn fq2(c0: &'static str, c1: &'static str) -> Fq2 {
+        Fp2::new(Bn254Field::from_str(c0), Bn254Field::from_str(c1))
+    }
+
+    #[test]
+    fn curve_add() {
+        let p1 = Bn254Curve::generator();
+
+        assert_eq!(p1.clone() + p1,
+            (
+                Bn254Field::from_str("1368015179489954701390400359078579693043519447331113978918064868415326638035"),
+                Bn254Field::from_str("9918110051302171585080402603319702774565515993150576347155970296011118125764"),
+            ).into()
+        );
+    }
+
+    #[test]
+    fn fp6_frobenius_coefficients() {
+        // (9 + u)^((p^k - 1) / 3), k = 1..5
+        let c1 = [
+            fq2(
+                "21575463638280843010398324269430826099269044274347216827212613867836435027261",
+                "10307601595873709700152284273816112264069230130616436755625194854815875713954",
+            ),
+            fq2("21888242871839275220042445260109153167277707414472061641714758635765020556616", "0"),
+            fq2(
+                "3772000881919853776433695186713858239009073593817195771773381919316419345261",
+                "2236595495967245188281701248203181795121068902605861227855261137820944008926",
+            ),
+            fq2("2203960485148121921418603742825762020974279258880205651966", "0"),
+            fq2(
+                "18429021223477853657660792034369865839114504446431234726392080002137598044644",
+                "9344045779998320333812420223237981029506012124075525679208581902008406485703",
+            ),
+        ];
+
+        // (9 + u)^((2 * p^k - 2) / 3), k = 1..5
+        let c2 = [
+            fq2(
+                "2581911344467009335267311115468803099551665605076196740867805258568234346338",
+                "19937756971775647987995932169929341994314640652964949448313374472400716661030",
+            ),
+            fq2("2203960485148121921418603742825762020974279258880205651966", "0"),
+            fq2(
+                "5324479202449903542726783395506214481928257762400643279780343368557297135718",
+                "16208900380737693084919495127334387981393726419856888799917914180988844123039",
+            ),
+            fq2("21888242871839275220042445260109153167277707414472061641714758635765020556616", "0"),
+            fq2(
+                "13981852324922362344252311234282257507216387789820983642040889267519694726527",
+                "7629828391165209371577384193250820201684255241773809077146787135900891633097",
+            ),
+        ];
+
+        let v: Fq6 = Fp6::new(Fq2::zero(), Fq2::one(), Fq2::zero());
+        let v2 = v.squared();
+
+        for k in 1..6 {
+            assert_eq!(Xi::frobenius_coefficient(3, k), c1[k - 1]);
+            assert_eq!(v.frobenius_map(k), Fp6::new(Fq2::zero(), c1[k - 1], Fq2::zero()));
+            assert_eq!(v2.frobenius_map(k), Fp6::new(Fq2::zero(), Fq2::zero(), c2[k - 1]));
+        }
+        assert_eq!(v.frobenius_map(6), v);
+    }
+
+    #[test]
+    fn fp12_frobenius_coefficients() {
+        // (9 + u)^((p^k - 1) / 6), k = 1..11
+        let c1 = [
+            fq2(
+                "8376118865763821496583973867626364092589906065868298776909617916018768340080",
+                "16469823323077808223889137241176536799009286646108169935659301613961712198316",
+            ),
+            fq2("21888242871839275220042445260109153167277707414472061641714758635765020556617", "0"),
+            fq2(
+                "11697423496358154304825782922584725312912383441159505038794027105778954184319",
+                "303847389135065887422783454877609941456349188919719272345083954437860409601",
+            ),
+            fq2("21888242871839275220042445260109153167277707414472061641714758635765020556616", "0"),
+            fq2(
+                "3321304630594332808241809054958361220322477375291206261884409189760185844239",
+                "5722266937896532885780051958958348231143373700109372999374820235121374419868",
+            ),
+            -Fq2::one(),
+            fq2(
+                "13512124006075453725662431877630910996106405091429524885779419978626457868503",
+                "5418419548761466998357268504080738289687024511189653727029736280683514010267",
+            ),
+            fq2("2203960485148121921418603742825762020974279258880205651966", "0"),
+            fq2(
+                "10190819375481120917420622822672549775783927716138318623895010788866272024264",
+                "21584395482704209334823622290379665147239961968378104390343953940207365798982",
+            ),
+            fq2("2203960485148121921418603742825762020974279258880205651967", "0"),
+            fq2(
+                "18566938241244942414004596690298913868373833782006617400804628704885040364344",
+                "16165975933942742336466353786298926857552937457188450663314217659523851788715",
+            ),
+        ];
+
+        // w^(p^k) = w * v^((p^k - 1) / 2) = w * (9 + u)^((p^k - 1) / 6)
+        let w: Fq12 = Fp2::new(Fq6::zero(), Fq6::one());
+
+        for k in 1..=11 {
+            assert_eq!(Xi::frobenius_coefficient(6, k), c1[k - 1]);
+            assert_eq!(w.frobenius_map(k), Fp2::new(Fq6::zero(), Fp6::from(c1[k - 1])));
+        }
+        assert_eq!(w.frobenius_map(12), w);
+    }
+
+    #[test]
+    fn fp12_arithmetic() {
+        let a: Fq12 = Fp2::new(
+            Fp6::new(fq2("1", "2"), fq2("3", "4"), fq2("5", "6")),
+            Fp6::new(fq2("7", "8"), fq2("9", "10"), fq2("11", "12")),
+        );
+
+        assert_eq!(a * a.inverse(), Fq12::one());
+        assert_eq!(a.frobenius_map(6), a.conjugate());
+        assert_eq!(a.frobenius_map(3).frobenius_map(9), a);
+
+        let root = a.squared().sqrt().unwrap();
+        assert!(root == a || root == -a);
+    }
+}
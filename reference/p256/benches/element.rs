@@ -0,0 +1,28 @@
+#![feature(test)]
+
+extern crate test;
+extern crate ng_uint as uint;
+extern crate ng_field as field;
+extern crate ng_p256 as p256;
+
+use uint::U256;
+use field::{MontgomeryElement, FieldValue};
+use p256::{P256Field, P256Element};
+
+#[bench]
+fn montgomery(b: &mut test::Bencher) {
+    let mut val = MontgomeryElement::<P256Field>::from(U256::from("11"));
+    let multiplyer = MontgomeryElement::<P256Field>::from(U256::from("13")).pow(U256::from("55"));
+    b.iter(|| {
+        val *= multiplyer;
+    });
+}
+
+#[bench]
+fn solinas(b: &mut test::Bencher) {
+    let mut val = P256Element::from(U256::from("11"));
+    let multiplyer = P256Element::from(U256::from("13")).pow(U256::from("55"));
+    b.iter(|| {
+        val *= multiplyer;
+    });
+}
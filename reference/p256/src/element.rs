@@ -0,0 +1,367 @@
+//! P-256 base field element with Solinas reduction
+//!
+//! p = 2^256 - 2^224 + 2^192 + 2^96 - 1 is a generalized Mersenne prime, so a 512-bit product
+//! is reduced by adding and subtracting rearrangements of its 32-bit words (FIPS 186-4, D.2.3).
+
+use std::ops::{Add, Mul, Neg, Sub, Div};
+
+use field::{Field, FieldElement, FieldValue, MontgomeryElement, Jacobi, Choice, ConditionallySelectable, ConstantTimeEq};
+use uint::{U256, adc, sbb, mac};
+
+use P256Field;
+
+fn modulus() -> [u64; 4] {
+    P256Field::MODULUS.0
+}
+
+/// a - p, returning (result, borrow)
+#[inline(always)]
+fn sub_modulus(a: [u64; 4]) -> ([u64; 4], u64) {
+    let p = modulus();
+    let mut r = [0u64; 4];
+    let mut borrow = 0;
+    for i in 0..4 {
+        let (v, b) = sbb(a[i], p[i], borrow);
+        r[i] = v;
+        borrow = b;
+    }
+    (r, borrow)
+}
+
+/// a + p, wrapping
+#[inline(always)]
+fn add_modulus(a: [u64; 4]) -> [u64; 4] {
+    let p = modulus();
+    let mut r = [0u64; 4];
+    let mut carry = 0;
+    for i in 0..4 {
+        let (v, c) = adc(a[i], p[i], carry);
+        r[i] = v;
+        carry = c;
+    }
+    r
+}
+
+/// Field element of P-256 base field in regular form, bit-compatible with `FieldElement<P256Field>`
+///
+/// Can be used as `P256Curve::Value` in place of `MontgomeryElement<P256Field>`, see `P256SolinasCurve`.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct P256Element {
+    value: U256,
+}
+
+impl P256Element {
+    /// New element from value, reduced modulo p
+    pub fn new(value: U256) -> Self {
+        // 2^256 < 2p, a single subtraction is enough
+        let (reduced, borrow) = sub_modulus(value.0);
+        if borrow == 1 { P256Element { value } } else { Self::from_limbs(reduced) }
+    }
+
+    /// Deconstruct and return canonical value
+    pub fn into_value(self) -> U256 {
+        self.value
+    }
+
+    /// Convert to generic field element
+    pub fn into_element(self) -> FieldElement<P256Field> {
+        FieldElement::from(self.value)
+    }
+
+    fn limbs(&self) -> [u64; 4] {
+        self.value.0
+    }
+
+    fn from_limbs(limbs: [u64; 4]) -> Self {
+        P256Element { value: U256::from_raw(limbs) }
+    }
+
+    /// Reduce 512-bit product given as little-endian limbs, NIST routine over 32-bit words
+    fn reduce(t: [u64; 8]) -> Self {
+        let mut c = [0i64; 16];
+        for i in 0..8 {
+            c[2 * i] = (t[i] & 0xffffffff) as i64;
+            c[2 * i + 1] = (t[i] >> 32) as i64;
+        }
+
+        // s1 + 2 s2 + 2 s3 + s4 + s5 - d1 - d2 - d3 - d4, word by word from the least significant
+        let mut words = [
+            c[0] + c[8] + c[9] - c[11] - c[12] - c[13] - c[14],
+            c[1] + c[9] + c[10] - c[12] - c[13] - c[14] - c[15],
+            c[2] + c[10] + c[11] - c[13] - c[14] - c[15],
+            c[3] + 2 * c[11] + 2 * c[12] + c[13] - c[15] - c[8] - c[9],
+            c[4] + 2 * c[12] + 2 * c[13] + c[14] - c[9] - c[10],
+            c[5] + 2 * c[13] + 2 * c[14] + c[15] - c[10] - c[11],
+            c[6] + 3 * c[14] + 2 * c[15] + c[13] - c[8] - c[9],
+            c[7] + 3 * c[15] + c[8] - c[10] - c[11] - c[12] - c[13],
+        ];
+
+        // propagate carries, the top word is a small signed multiple of 2^256
+        let carry = Self::propagate(&mut words);
+
+        // 2^256 = 2^224 - 2^192 - 2^96 + 1 mod p, fold the top word back once more
+        words[0] += carry;
+        words[3] -= carry;
+        words[6] -= carry;
+        words[7] += carry;
+        let carry = Self::propagate(&mut words);
+
+        let mut r = [0u64; 4];
+        for (i, &word) in words.iter().enumerate() {
+            r[i / 2] |= (word as u64) << (32 * (i % 2));
+        }
+
+        // the value is now within (-p, 2p)
+        if carry < 0 {
+            return Self::from_limbs(add_modulus(r));
+        }
+        let (reduced, borrow) = sub_modulus(r);
+        if carry > 0 || borrow == 0 { Self::from_limbs(reduced) } else { Self::from_limbs(r) }
+    }
+
+    /// Normalize signed words to [0, 2^32), returning the signed carry out of the top word
+    #[inline(always)]
+    fn propagate(words: &mut [i64; 8]) -> i64 {
+        let mut carry = 0;
+        for word in words.iter_mut() {
+            let v = *word + carry;
+            *word = v & 0xffffffff;
+            carry = v >> 32;
+        }
+        carry
+    }
+
+    /// self^-1 by Fermat's little theorem, zero for zero
+    fn inverse(self) -> Self {
+        // p - 2
+        self.pow_vartime(U256::from_raw([0xfffffffffffffffd, 0x00000000ffffffff, 0x0000000000000000, 0xffffffff00000001]))
+    }
+}
+
+impl From<U256> for P256Element {
+    fn from(value: U256) -> Self {
+        P256Element::new(value)
+    }
+}
+
+impl From<FieldElement<P256Field>> for P256Element {
+    fn from(element: FieldElement<P256Field>) -> Self {
+        P256Element { value: element.into_value() }
+    }
+}
+
+impl From<MontgomeryElement<P256Field>> for P256Element {
+    fn from(element: MontgomeryElement<P256Field>) -> Self {
+        P256Element { value: element.into_reduced_value() }
+    }
+}
+
+impl Add for P256Element {
+    type Output = Self;
+    fn add(self, other: Self) -> Self {
+        let (a, b) = (self.limbs(), other.limbs());
+
+        let mut r = [0u64; 4];
+        let mut carry = 0;
+        for i in 0..4 {
+            let (v, c) = adc(a[i], b[i], carry);
+            r[i] = v;
+            carry = c;
+        }
+
+        // a + b < 2p, subtract p on overflow or when the sum is not less than p
+        let (reduced, borrow) = sub_modulus(r);
+        if carry == 1 || borrow == 0 { Self::from_limbs(reduced) } else { Self::from_limbs(r) }
+    }
+}
+
+impl Sub for P256Element {
+    type Output = Self;
+    fn sub(self, other: Self) -> Self {
+        let (a, b) = (self.limbs(), other.limbs());
+
+        let mut r = [0u64; 4];
+        let mut borrow = 0;
+        for i in 0..4 {
+            let (v, bo) = sbb(a[i], b[i], borrow);
+            r[i] = v;
+            borrow = bo;
+        }
+
+        if borrow == 1 { Self::from_limbs(add_modulus(r)) } else { Self::from_limbs(r) }
+    }
+}
+
+impl Neg for P256Element {
+    type Output = Self;
+    fn neg(self) -> Self {
+        Self::zero() - self
+    }
+}
+
+impl Mul for P256Element {
+    type Output = Self;
+    fn mul(self, other: Self) -> Self {
+        let (a, b) = (self.limbs(), other.limbs());
+
+        let mut t = [0u64; 8];
+        for i in 0..4 {
+            let mut carry = 0;
+            for j in 0..4 {
+                let (v, c) = mac(t[i + j], a[i], b[j], carry);
+                t[i + j] = v;
+                carry = c;
+            }
+            t[i + 4] = carry;
+        }
+
+        Self::reduce(t)
+    }
+}
+
+impl Mul<u32> for P256Element {
+    type Output = Self;
+    fn mul(self, other: u32) -> Self {
+        self * Self::from_limbs([other as u64, 0, 0, 0])
+    }
+}
+
+impl Div for P256Element {
+    type Output = Self;
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn div(self, other: Self) -> Self {
+        assert!(other != Self::zero(), "division by zero");
+        self * other.inverse()
+    }
+}
+
+impl ConditionallySelectable for P256Element {
+    fn conditional_select(a: &Self, b: &Self, choice: Choice) -> Self {
+        P256Element { value: U256::conditional_select(&a.value, &b.value, choice) }
+    }
+}
+
+impl ConstantTimeEq for P256Element {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.value.ct_eq(&other.value)
+    }
+}
+
+impl FieldValue for P256Element {
+    type Value = U256;
+
+    fn one() -> Self {
+        Self::from_limbs([1, 0, 0, 0])
+    }
+
+    fn zero() -> Self {
+        Self::from_limbs([0, 0, 0, 0])
+    }
+
+    fn characteristic() -> U256 {
+        P256Field::MODULUS
+    }
+
+    fn sqrt(self) -> Option<Self> {
+        // p = 3 mod 4, so the root is self^((p + 1) / 4)
+        let exponent = U256::from_raw([0x0000000000000000, 0x0000000040000000, 0x4000000000000000, 0x3fffffffc0000000]);
+
+        let root = self.pow_vartime(exponent);
+        if root.squared() == self { Some(root) } else { None }
+    }
+
+    fn legendre(self) -> i8 {
+        self.value.jacobi(P256Field::MODULUS)
+    }
+}
+
+impl_field_ops!(P256Element);
+
+#[cfg(test)]
+mod tests {
+
+    use super::P256Element;
+    use field::{Field, FieldElement, FieldValue, MontgomeryElement};
+    use uint::U256;
+    use quickcheck::TestResult;
+    use P256Field;
+
+    type Mont = MontgomeryElement<P256Field>;
+
+    #[test]
+    fn constants() {
+        let minus_one = -P256Element::one();
+
+        assert_eq!(minus_one.into_element(), -FieldElement::<P256Field>::one());
+        assert_eq!(minus_one * minus_one, P256Element::one());
+        assert_eq!(P256Element::from(P256Field::MODULUS), P256Element::zero());
+        assert_eq!(P256Element::from(Mont::from(U256::from("3"))).into_value(), U256::from("3"));
+
+        let five = P256Element::one() * 5;
+        assert_eq!(five.squared().sqrt().map(|r| r.squared()), Some(five.squared()));
+    }
+
+    fn pair(x: (u64, u64, u64, u64)) -> (P256Element, Mont) {
+        let value = U256::from_raw([x.0, x.1, x.2, x.3]);
+        (value.into(), value.into())
+    }
+
+    /// Values just below p, where carries and final corrections are extreme
+    fn edge(x: (u64, u64, u64, u64)) -> (P256Element, Mont) {
+        let mut limbs = P256Field::MODULUS.0;
+        limbs[0] = limbs[0].wrapping_sub(x.0 % 2048);
+        pair((limbs[0], limbs[1], limbs[2], limbs[3]))
+    }
+
+    #[test]
+    fn bit_compatible() {
+        use std::mem;
+
+        let x = FieldElement::<P256Field>::from(U256::from("48439561293906451759052585252797914202762949526041747995844080717082404635286"));
+        let y = P256Element::from(x);
+
+        assert_eq!(mem::size_of::<P256Element>(), mem::size_of::<FieldElement<P256Field>>());
+        assert_eq!(y.into_value(), x.into_value());
+        assert_eq!(y.into_element(), x);
+    }
+
+    quickcheck! {
+        fn add_sub_neg_equal_reference(x: (u64, u64, u64, u64), y: (u64, u64, u64, u64)) -> bool {
+            let ((x_s, x_r), (y_s, y_r)) = (pair(x), pair(y));
+            let ((e_s, e_r), (f_s, f_r)) = (edge(x), edge(y));
+
+            x_s + y_s == (x_r + y_r).into() &&
+                x_s - y_s == (x_r - y_r).into() &&
+                -x_s == (-x_r).into() &&
+                e_s + f_s == (e_r + f_r).into() &&
+                e_s - x_s == (e_r - x_r).into() &&
+                x_s - e_s == (x_r - e_r).into()
+        }
+
+        fn mul_equals_reference(x: (u64, u64, u64, u64), y: (u64, u64, u64, u64), z: u32) -> bool {
+            let ((x_s, x_r), (y_s, y_r)) = (pair(x), pair(y));
+            let ((e_s, e_r), (f_s, f_r)) = (edge(x), edge(y));
+
+            x_s * y_s == (x_r * y_r).into() &&
+                e_s * f_s == (e_r * f_r).into() &&
+                e_s * y_s == (e_r * y_r).into() &&
+                e_s * z == (e_r * z).into() &&
+                x_s * z == (x_r * z).into()
+        }
+
+        fn div_sqrt_legendre_equal_reference(x: (u64, u64, u64, u64), y: (u64, u64, u64, u64)) -> TestResult {
+            let ((x_s, x_r), (y_s, y_r)) = (pair(x), pair(y));
+
+            if y_r == Mont::zero() {
+                return TestResult::discard();
+            }
+
+            TestResult::from_bool(
+                x_s / y_s == (x_r / y_r).into() &&
+                    x_s.legendre() == x_r.legendre() &&
+                    x_s.sqrt().map(|r| r.squared()) == x_r.sqrt().map(|r| r.squared().into())
+            )
+        }
+    }
+}
@@ -1,12 +1,16 @@
-extern crate ng_field as field;
+#[macro_use] extern crate ng_field as field;
 extern crate ng_uint as uint;
 extern crate ng_curve as curve;
-extern crate bigint;
+#[cfg(test)] #[macro_use] extern crate quickcheck;
+
+mod element;
 
 use uint::U256;
-use field::MontgomeryElement;
+use field::{FieldValue, MontgomeryElement};
 use curve::{Curve, AffinePoint};
 
+pub use element::P256Element;
+
 #[derive(Copy, Clone, Debug, PartialEq)]
 pub struct P256Field;
 
@@ -14,37 +18,49 @@ impl field::Field for P256Field {
     type Value = U256;
 
     // 115792089210356248762697446949407573530086143415290314195533631308867097853951
-    const MODULUS: U256 = U256(bigint::U256(
+    const MODULUS: U256 = U256::from_raw(
         [
             0xffffffffffffffff,
             0x00000000ffffffff,
             0x0000000000000000,
             0xffffffff00000001,
         ]
-    ));
+    );
+}
 
+impl field::MontgomeryField for P256Field {
     // 115792089237316195423570985008687907853269984665640564039457584007913129639936
-    const R: U256 = U256(bigint::U256(
+    const R: U256 = U256::from_raw(
         [
             0x0000000000000001,
             0xFFFFFFFF00000000,
             0xFFFFFFFFFFFFFFFF,
             0x00000000FFFFFFFE,
         ]
-    ));
+    );
 
     // 115792089183396302114378112356516095823261736990586219612555396166510339686400
-    const R_INVERSE: U256 = U256(bigint::U256(
+    const R_INVERSE: U256 = U256::from_raw(
         [
             0x0000000300000000,
             0x00000001FFFFFFFE,
             0xFFFFFFFD00000002,
             0xFFFFFFFE00000003,
         ]
-    ));
+    );
+
+    const INV: u64 = 0x0000000000000001;
 }
 
+check_montgomery_constants!(
+    <P256Field as field::Field>::MODULUS.0,
+    <P256Field as field::MontgomeryField>::R.0,
+    <P256Field as field::MontgomeryField>::R_INVERSE.0,
+    <P256Field as field::MontgomeryField>::INV
+);
+
 impl P256Field {
+    #[allow(clippy::should_implement_trait)]
     pub fn from_str(v: &'static str) -> MontgomeryElement<Self> {
         U256::from(v).into()
     }
@@ -54,45 +70,69 @@ impl P256Field {
     }
 
     pub fn from_u64(x: u64) -> MontgomeryElement<Self> {
-        U256(x.into()).into()
+        U256::from(x).into()
     }
 }
 
+//
+// 0x6B17D1F2E12C4247F8BCE6E563A440F277037D812DEB33A0F4A13945D898C296,
+// 0x4FE342E2FE1A7F9B8EE7EB4A7C0F9E162BCE33576B315ECECBB6406837BF51F5
+//
+fn generator<V: FieldValue<Value=U256> + From<U256>>() -> (V, V) {
+    (
+        U256::from_raw([
+            0xF4A13945D898C296,
+            0x77037D812DEB33A0,
+            0xF8BCE6E563A440F2,
+            0x6B17D1F2E12C4247,
+        ]).into(),
+        U256::from_raw([
+            0xCBB6406837BF51F5,
+            0x2BCE33576B315ECE,
+            0x8EE7EB4A7C0F9E16,
+            0x4FE342E2FE1A7F9B,
+        ]).into()
+    )
+}
+
+// 115792089210356248762697446949407573530086143415290314195533631308867097853948 (-3 mod p)
+fn a<V: FieldValue<Value=U256> + From<U256>>() -> V {
+    U256::from_raw([
+        0xFFFFFFFFFFFFFFFC,
+        0x00000000FFFFFFFF,
+        0x0000000000000000,
+        0xFFFFFFFF00000001,
+    ]).into()
+}
+
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub struct P256Curve;
 
 impl Curve for P256Curve {
     type Value = MontgomeryElement<P256Field>;
 
-    //
-    // 0x6B17D1F2E12C4247F8BCE6E563A440F277037D812DEB33A0F4A13945D898C296,
-    // 0x4FE342E2FE1A7F9B8EE7EB4A7C0F9E162BCE33576B315ECECBB6406837BF51F5
-    //
     fn generator() -> AffinePoint<Self> {
-        (
-            U256::from_raw([
-                0xF4A13945D898C296,
-                0x77037D812DEB33A0,
-                0xF8BCE6E563A440F2,
-                0x6B17D1F2E12C4247,
-            ]),
-            U256::from_raw([
-                0xCBB6406837BF51F5,
-                0x2BCE33576B315ECE,
-                0x8EE7EB4A7C0F9E16,
-                0x4FE342E2FE1A7F9B,
-            ])
-        ).into()
+        generator::<Self::Value>().into()
     }
 
-    // 115792089210356248762697446949407573530086143415290314195533631308867097853948 (-3 mod p)
     fn a() -> Self::Value {
-        U256::from_raw([
-            0xFFFFFFFFFFFFFFFC,
-            0x00000000FFFFFFFF,
-            0x0000000000000000,
-            0xFFFFFFFF00000001,
-        ]).into()
+        a()
+    }
+}
+
+/// P-256 with Solinas field arithmetic
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct P256SolinasCurve;
+
+impl Curve for P256SolinasCurve {
+    type Value = P256Element;
+
+    fn generator() -> AffinePoint<Self> {
+        generator::<Self::Value>().into()
+    }
+
+    fn a() -> Self::Value {
+        a()
     }
 }
 
@@ -151,4 +191,51 @@ mod tests {
             ).into()
         );
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn b() {
+        let b = P256Field::from_hex("5AC635D8AA3A93E7B3EBBD55769886BC651D06B0CC53B0F63BCE3C3E27D2604B");
+        assert_eq!(P256Curve::b(), b);
+    }
+
+    #[test]
+    fn solinas_curve() {
+        use super::{P256Element, P256SolinasCurve};
+
+        let p1: JacobianPoint<P256Curve> = P256Curve::generator().into();
+        let p2: JacobianPoint<P256SolinasCurve> = P256SolinasCurve::generator().into();
+        assert_eq!(P256SolinasCurve::b(), P256Element::from(P256Curve::b()));
+
+        let scalar = U256::from("115792089210356248762697446949407573529996955224135760342422259061068512044361");
+        let (x1, y1) = AffinePoint::from(p1 * scalar).into_parts();
+        let (x2, y2) = AffinePoint::from(p2 * scalar).into_parts();
+
+        assert_eq!((x2, y2), (x1.into(), y1.into()));
+    }
+
+    #[test]
+    fn bytes() {
+        use field::{MontgomeryElement, DecodeError};
+
+        let (x, _) = P256Curve::generator().into_parts();
+        let encoded = [
+            0x6B, 0x17, 0xD1, 0xF2, 0xE1, 0x2C, 0x42, 0x47,
+            0xF8, 0xBC, 0xE6, 0xE5, 0x63, 0xA4, 0x40, 0xF2,
+            0x77, 0x03, 0x7D, 0x81, 0x2D, 0xEB, 0x33, 0xA0,
+            0xF4, 0xA1, 0x39, 0x45, 0xD8, 0x98, 0xC2, 0x96,
+        ];
+
+        assert_eq!(x.to_bytes_be(), encoded.to_vec());
+        assert_eq!(MontgomeryElement::<P256Field>::from_bytes_be(&encoded), Ok(x));
+
+        let mut reversed = encoded;
+        reversed.reverse();
+        assert_eq!(x.to_bytes_le(), reversed.to_vec());
+        assert_eq!(MontgomeryElement::<P256Field>::from_bytes_le(&reversed), Ok(x));
+
+        // p itself is not a canonical encoding
+        let mut modulus = [0u8; 32];
+        field::Scalar::write_be_bytes(&U256::from_hex("ffffffff00000001000000000000000000000000ffffffffffffffffffffffff"), &mut modulus);
+        assert_eq!(MontgomeryElement::<P256Field>::from_bytes_be(&modulus), Err(DecodeError::NotCanonical));
+    }
+}
@@ -0,0 +1,28 @@
+#![feature(test)]
+
+extern crate test;
+extern crate ng_uint as uint;
+extern crate ng_field as field;
+extern crate ng_secp256k1 as secp256k1;
+
+use uint::U256;
+use field::{FieldElement, FieldValue};
+use secp256k1::{Secp256k1Field, Secp256k1Element};
+
+#[bench]
+fn regular(b: &mut test::Bencher) {
+    let mut val = FieldElement::<Secp256k1Field>::from(U256::from("11"));
+    let multiplyer = FieldElement::<Secp256k1Field>::from(U256::from("13")).pow(U256::from("55"));
+    b.iter(|| {
+        val *= multiplyer;
+    });
+}
+
+#[bench]
+fn special(b: &mut test::Bencher) {
+    let mut val = Secp256k1Element::from(U256::from("11"));
+    let multiplyer = Secp256k1Element::from(U256::from("13")).pow(U256::from("55"));
+    b.iter(|| {
+        val *= multiplyer;
+    });
+}
@@ -0,0 +1,344 @@
+//! secp256k1 base field element with special-form reduction
+//!
+//! p = 2^256 - 2^32 - 977, so 2^256 = 2^32 + 977 mod p and the high half of a product
+//! folds into the low half with a multiplication by a 33-bit constant.
+
+use std::ops::{Add, Mul, Neg, Sub, Div};
+
+use field::{Field, FieldElement, FieldValue, Jacobi, Choice, ConditionallySelectable, ConstantTimeEq};
+use uint::{U256, adc, sbb, mac};
+
+use Secp256k1Field;
+
+/// 2^256 mod p
+const C: u64 = 0x1000003D1;
+
+/// a + c, returning (result, carry), for small c spanning at most two limbs
+#[inline(always)]
+fn add_small(a: [u64; 4], c: u128) -> ([u64; 4], u64) {
+    let (r0, carry) = adc(a[0], c as u64, 0);
+    let (r1, carry) = adc(a[1], (c >> 64) as u64, carry);
+    let (r2, carry) = adc(a[2], 0, carry);
+    let (r3, carry) = adc(a[3], 0, carry);
+    ([r0, r1, r2, r3], carry)
+}
+
+/// a mod p for a < 2^256, a single conditional subtraction
+#[inline(always)]
+fn normalize(a: [u64; 4]) -> [u64; 4] {
+    // a >= p exactly when a + (2^256 - p) overflows
+    let (reduced, carry) = add_small(a, C as u128);
+    if carry == 1 { reduced } else { a }
+}
+
+/// Field element of secp256k1 base field, bit-compatible with `FieldElement<Secp256k1Field>`
+///
+/// Holds the same canonical value, so conversions between the two are free, while
+/// multiplication uses the special form of the modulus instead of a 512-bit division.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Secp256k1Element {
+    value: U256,
+}
+
+impl Secp256k1Element {
+    /// New element from value, reduced modulo p
+    pub fn new(value: U256) -> Self {
+        Secp256k1Element { value: U256::from_raw(normalize(value.0)) }
+    }
+
+    /// Deconstruct and return canonical value
+    pub fn into_value(self) -> U256 {
+        self.value
+    }
+
+    /// Convert to generic field element
+    pub fn into_element(self) -> FieldElement<Secp256k1Field> {
+        FieldElement::from(self.value)
+    }
+
+    fn limbs(&self) -> [u64; 4] {
+        self.value.0
+    }
+
+    fn from_limbs(limbs: [u64; 4]) -> Self {
+        Secp256k1Element { value: U256::from_raw(limbs) }
+    }
+
+    /// Reduce 512-bit product given as little-endian limbs
+    #[inline(always)]
+    fn reduce(t: [u64; 8]) -> Self {
+        // low + high * C, high * C is at most 289 bits
+        let mut r = [0u64; 4];
+        let mut carry = 0;
+        for i in 0..4 {
+            let (v, c) = mac(t[i], t[i + 4], C, carry);
+            r[i] = v;
+            carry = c;
+        }
+
+        // carry < 2^34, carry * C < 2^67
+        let (r, carry) = add_small(r, (carry as u128) * (C as u128));
+
+        // wrapped around 2^256 at most once, r is small afterwards and cannot overflow again
+        let (r, _) = add_small(r, (carry as u128) * (C as u128));
+
+        Self::from_limbs(normalize(r))
+    }
+
+    /// self^-1 by Fermat's little theorem, zero for zero
+    fn inverse(self) -> Self {
+        let mut exponent = Secp256k1Field::MODULUS.0;
+        exponent[0] -= 2;
+        self.pow_vartime(U256::from_raw(exponent))
+    }
+}
+
+impl From<FieldElement<Secp256k1Field>> for Secp256k1Element {
+    fn from(element: FieldElement<Secp256k1Field>) -> Self {
+        Secp256k1Element { value: element.into_value() }
+    }
+}
+
+impl From<U256> for Secp256k1Element {
+    fn from(value: U256) -> Self {
+        Secp256k1Element::new(value)
+    }
+}
+
+impl Add for Secp256k1Element {
+    type Output = Self;
+    fn add(self, other: Self) -> Self {
+        let (a, b) = (self.limbs(), other.limbs());
+
+        let mut r = [0u64; 4];
+        let mut carry = 0;
+        for i in 0..4 {
+            let (v, c) = adc(a[i], b[i], carry);
+            r[i] = v;
+            carry = c;
+        }
+
+        // a + b < 2p, subtract p on overflow or when the sum is not less than p
+        if carry == 1 {
+            Self::from_limbs(add_small(r, C as u128).0)
+        } else {
+            Self::from_limbs(normalize(r))
+        }
+    }
+}
+
+impl Sub for Secp256k1Element {
+    type Output = Self;
+    fn sub(self, other: Self) -> Self {
+        let (a, b) = (self.limbs(), other.limbs());
+
+        let mut r = [0u64; 4];
+        let mut borrow = 0;
+        for i in 0..4 {
+            let (v, bo) = sbb(a[i], b[i], borrow);
+            r[i] = v;
+            borrow = bo;
+        }
+
+        // wrapped below zero, adding p is subtracting C modulo 2^256
+        if borrow == 1 {
+            let mut borrow = 0;
+            for (i, limb) in r.iter_mut().enumerate() {
+                let (v, bo) = sbb(*limb, if i == 0 { C } else { 0 }, borrow);
+                *limb = v;
+                borrow = bo;
+            }
+        }
+
+        Self::from_limbs(r)
+    }
+}
+
+impl Neg for Secp256k1Element {
+    type Output = Self;
+    fn neg(self) -> Self {
+        Self::zero() - self
+    }
+}
+
+impl Mul for Secp256k1Element {
+    type Output = Self;
+    fn mul(self, other: Self) -> Self {
+        let (a, b) = (self.limbs(), other.limbs());
+
+        let mut t = [0u64; 8];
+        for i in 0..4 {
+            let mut carry = 0;
+            for j in 0..4 {
+                let (v, c) = mac(t[i + j], a[i], b[j], carry);
+                t[i + j] = v;
+                carry = c;
+            }
+            t[i + 4] = carry;
+        }
+
+        Self::reduce(t)
+    }
+}
+
+impl Mul<u32> for Secp256k1Element {
+    type Output = Self;
+    fn mul(self, other: u32) -> Self {
+        let a = self.limbs();
+
+        let mut t = [0u64; 8];
+        let mut carry = 0;
+        for i in 0..4 {
+            let (v, c) = mac(0, a[i], other as u64, carry);
+            t[i] = v;
+            carry = c;
+        }
+        t[4] = carry;
+
+        Self::reduce(t)
+    }
+}
+
+impl Div for Secp256k1Element {
+    type Output = Self;
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn div(self, other: Self) -> Self {
+        assert!(other != Self::zero(), "division by zero");
+        self * other.inverse()
+    }
+}
+
+impl ConditionallySelectable for Secp256k1Element {
+    fn conditional_select(a: &Self, b: &Self, choice: Choice) -> Self {
+        Secp256k1Element { value: U256::conditional_select(&a.value, &b.value, choice) }
+    }
+}
+
+impl ConstantTimeEq for Secp256k1Element {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.value.ct_eq(&other.value)
+    }
+}
+
+impl FieldValue for Secp256k1Element {
+    type Value = U256;
+
+    fn one() -> Self {
+        Self::from_limbs([1, 0, 0, 0])
+    }
+
+    fn zero() -> Self {
+        Self::from_limbs([0, 0, 0, 0])
+    }
+
+    fn characteristic() -> U256 {
+        Secp256k1Field::MODULUS
+    }
+
+    fn sqrt(self) -> Option<Self> {
+        // p = 3 mod 4, so the root is self^((p + 1) / 4)
+        let exponent = U256::from_raw([
+            0xFFFFFFFFBFFFFF0C,
+            0xFFFFFFFFFFFFFFFF,
+            0xFFFFFFFFFFFFFFFF,
+            0x3FFFFFFFFFFFFFFF,
+        ]);
+
+        let root = self.pow_vartime(exponent);
+        if root.squared() == self { Some(root) } else { None }
+    }
+
+    fn legendre(self) -> i8 {
+        self.value.jacobi(Secp256k1Field::MODULUS)
+    }
+}
+
+impl_field_ops!(Secp256k1Element);
+
+#[cfg(test)]
+mod tests {
+
+    use super::Secp256k1Element;
+    use field::{Field, FieldElement, FieldValue};
+    use uint::U256;
+    use quickcheck::TestResult;
+    use Secp256k1Field;
+
+    #[test]
+    fn constants() {
+        let minus_one = -Secp256k1Element::one();
+
+        assert_eq!(minus_one.into_element(), -FieldElement::<Secp256k1Field>::one());
+        assert_eq!(minus_one * minus_one, Secp256k1Element::one());
+        assert_eq!(Secp256k1Element::from(Secp256k1Field::MODULUS), Secp256k1Element::zero());
+        assert_eq!(Secp256k1Element::from(U256::from_raw([!0; 4])).into_value(), U256::from("4294968272"));
+
+        let five = Secp256k1Element::one() * 5;
+        assert_eq!(five.sqrt(), None);
+        assert_eq!(five.squared().sqrt().map(|r| r.squared()), Some(five.squared()));
+    }
+
+    fn pair(x: (u64, u64, u64, u64)) -> (Secp256k1Element, FieldElement<Secp256k1Field>) {
+        let value = U256::from_raw([x.0, x.1, x.2, x.3]);
+        (value.into(), value.into())
+    }
+
+    /// Values just below p, where carries and final corrections are extreme
+    fn edge(x: (u64, u64, u64, u64)) -> (Secp256k1Element, FieldElement<Secp256k1Field>) {
+        let mut limbs = Secp256k1Field::MODULUS.0;
+        limbs[0] = limbs[0].wrapping_sub(x.0 % 2048);
+        pair((limbs[0], limbs[1], limbs[2], limbs[3]))
+    }
+
+    #[test]
+    fn bit_compatible() {
+        use std::mem;
+
+        let x = FieldElement::<Secp256k1Field>::from(U256::from("55066263022277343669578718895168534326250603453777594175500187360389116729240"));
+        let y = Secp256k1Element::from(x);
+
+        assert_eq!(mem::size_of::<Secp256k1Element>(), mem::size_of::<FieldElement<Secp256k1Field>>());
+        assert_eq!(y.into_value(), x.into_value());
+        assert_eq!(y.into_element(), x);
+    }
+
+    quickcheck! {
+        fn add_sub_neg_equal_reference(x: (u64, u64, u64, u64), y: (u64, u64, u64, u64)) -> bool {
+            let ((x_s, x_r), (y_s, y_r)) = (pair(x), pair(y));
+            let ((e_s, e_r), (f_s, f_r)) = (edge(x), edge(y));
+
+            x_s + y_s == (x_r + y_r).into() &&
+                x_s - y_s == (x_r - y_r).into() &&
+                -x_s == (-x_r).into() &&
+                e_s + f_s == (e_r + f_r).into() &&
+                e_s - x_s == (e_r - x_r).into() &&
+                x_s - e_s == (x_r - e_r).into()
+        }
+
+        fn mul_equals_reference(x: (u64, u64, u64, u64), y: (u64, u64, u64, u64), z: u32) -> bool {
+            let ((x_s, x_r), (y_s, y_r)) = (pair(x), pair(y));
+            let ((e_s, e_r), (f_s, f_r)) = (edge(x), edge(y));
+
+            x_s * y_s == (x_r * y_r).into() &&
+                e_s * f_s == (e_r * f_r).into() &&
+                e_s * y_s == (e_r * y_r).into() &&
+                e_s * z == (e_r * z).into() &&
+                x_s * z == (x_r * z).into()
+        }
+
+        fn div_sqrt_legendre_equal_reference(x: (u64, u64, u64, u64), y: (u64, u64, u64, u64)) -> TestResult {
+            let ((x_s, x_r), (y_s, y_r)) = (pair(x), pair(y));
+
+            if y_r == FieldElement::<Secp256k1Field>::zero() {
+                return TestResult::discard();
+            }
+
+            TestResult::from_bool(
+                x_s / y_s == (x_r / y_r).into() &&
+                    x_s.legendre() == x_r.legendre() &&
+                    x_s.sqrt().map(|r| r.squared()) == x_r.sqrt().map(|r| r.squared().into())
+            )
+        }
+    }
+}
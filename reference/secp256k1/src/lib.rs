@@ -1,12 +1,16 @@
-extern crate ng_field as field;
+#[macro_use] extern crate ng_field as field;
 extern crate ng_uint as uint;
 extern crate ng_curve as curve;
-extern crate bigint;
+#[cfg(test)] #[macro_use] extern crate quickcheck;
+
+mod element;
 
 use uint::U256;
 use field::{FieldElement, FieldValue};
 use curve::{Curve, AffinePoint};
 
+pub use element::Secp256k1Element;
+
 #[derive(Copy, Clone, Debug, PartialEq)]
 pub struct Secp256k1Field;
 
@@ -14,27 +18,28 @@ impl field::Field for Secp256k1Field {
     type Value = U256;
 
     // 115792089237316195423570985008687907853269984665640564039457584007908834671663
-    const MODULUS: U256 = U256(bigint::U256(
+    const MODULUS: U256 = U256::from_raw(
         [
             0xFFFFFFFEFFFFFC2F,
             0xFFFFFFFFFFFFFFFF,
             0xFFFFFFFFFFFFFFFF,
             0xFFFFFFFFFFFFFFFF,
         ]
-    ));
+    );
+}
 
-    // don't use it for montgomery form
-    const R: U256 = U256(bigint::U256([0, 0, 0, 0]));
-    const R_INVERSE: U256 = U256(bigint::U256([0, 0, 0, 0]));
+impl field::BarrettField for Secp256k1Field {
+    const MU: U256 = U256::from_raw(field::barrett_mu(<Self as field::Field>::MODULUS.0));
 }
 
 impl Secp256k1Field {
+    #[allow(clippy::should_implement_trait)]
     pub fn from_str(v: &'static str) -> FieldElement<Self> {
         U256::from(v).into()
     }
 
     pub fn from_u64(x: u64) -> FieldElement<Self> {
-        U256(x.into()).into()
+        U256::from(x).into()
     }
 }
 
@@ -105,4 +110,25 @@ mod tests {
             ).into()
         )
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn b() {
+        use field::FieldValue;
+
+        assert_eq!(Secp256k1Curve::b(), Secp256k1Field::from_u64(7));
+        assert_eq!(Secp256k1Field::from_u64(5).sqrt(), None);
+    }
+
+    #[test]
+    fn barrett() {
+        use field::{BarrettElement, FieldValue};
+
+        let x = Secp256k1Field::from_str("44828909320452647301050893743220441820626641267700871452737776290545709136354");
+        let y = Secp256k1Field::from_str("32670510020758816978083085130507043184471273380659243275938904335757337482424");
+        let (x_b, y_b) = (BarrettElement::from_element(x), BarrettElement::from_element(y));
+
+        assert_eq!((x_b * y_b).into_element(), x * y);
+        assert_eq!((x_b / y_b).into_element(), x / y);
+        assert_eq!(x_b.pow(U256::from("1000000007")).into_element(), x.pow(U256::from("1000000007")));
+    }
+}
@@ -0,0 +1,47 @@
+#![feature(test)]
+
+extern crate test;
+extern crate ng_uint as uint;
+extern crate ng_field as field;
+
+use uint::U256;
+use field::{BarrettElement, FieldElement, FieldValue};
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+struct Secp256k1Field;
+
+impl field::Field for Secp256k1Field {
+    type Value = U256;
+
+    // 115792089237316195423570985008687907853269984665640564039457584007908834671663
+    const MODULUS: U256 = U256::from_raw(
+        [
+            0xFFFFFFFEFFFFFC2F,
+            0xFFFFFFFFFFFFFFFF,
+            0xFFFFFFFFFFFFFFFF,
+            0xFFFFFFFFFFFFFFFF,
+        ]
+    );
+}
+
+impl field::BarrettField for Secp256k1Field {
+    const MU: U256 = U256::from_raw(field::barrett_mu(<Self as field::Field>::MODULUS.0));
+}
+
+#[bench]
+fn regular(b: &mut test::Bencher) {
+    let mut val = FieldElement::<Secp256k1Field>::from(U256::from("11"));
+    let multiplyer = FieldElement::<Secp256k1Field>::from(U256::from("13")).pow(U256::from("55"));
+    b.iter(|| {
+        val *= multiplyer;
+    });
+}
+
+#[bench]
+fn barrett(b: &mut test::Bencher) {
+    let mut val = BarrettElement::<Secp256k1Field>::from(U256::from("11"));
+    let multiplyer = BarrettElement::<Secp256k1Field>::from(U256::from("13")).pow(U256::from("55"));
+    b.iter(|| {
+        val *= multiplyer;
+    });
+}
@@ -0,0 +1,44 @@
+#![feature(test)]
+
+extern crate test;
+extern crate ng_uint as uint;
+
+use uint::{Uint, U256, U512, U576};
+
+fn value<const LIMBS: usize>(seed: u64) -> Uint<LIMBS> {
+    let mut limbs = [0u64; LIMBS];
+    for (i, limb) in limbs.iter_mut().enumerate() {
+        *limb = seed.wrapping_mul(0x9e3779b97f4a7c15).rotate_left(i as u32 * 7) ^ i as u64;
+    }
+    Uint::from_raw(limbs)
+}
+
+#[bench]
+fn widening_mul_u256(b: &mut test::Bencher) {
+    let (x, y): (U256, U256) = (value(1), value(2));
+    b.iter(|| test::black_box(x).widening_mul(test::black_box(y)));
+}
+
+#[bench]
+fn widening_mul_u512(b: &mut test::Bencher) {
+    let (x, y): (U512, U512) = (value(1), value(2));
+    b.iter(|| test::black_box(x).widening_mul(test::black_box(y)));
+}
+
+#[bench]
+fn widening_mul_u576(b: &mut test::Bencher) {
+    let (x, y): (U576, U576) = (value(1), value(2));
+    b.iter(|| test::black_box(x).widening_mul(test::black_box(y)));
+}
+
+#[bench]
+fn widening_mul_u1024(b: &mut test::Bencher) {
+    let (x, y): (Uint<16>, Uint<16>) = (value(1), value(2));
+    b.iter(|| test::black_box(x).widening_mul(test::black_box(y)));
+}
+
+#[bench]
+fn widening_mul_u4096(b: &mut test::Bencher) {
+    let (x, y): (Uint<64>, Uint<64>) = (value(1), value(2));
+    b.iter(|| test::black_box(x).widening_mul(test::black_box(y)));
+}
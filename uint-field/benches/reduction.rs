@@ -3,10 +3,9 @@
 extern crate test;
 extern crate ng_uint as uint;
 extern crate ng_field as field;
-extern crate bigint;
 
 use uint::U256;
-use field::{MontgomeryElement, FieldValue};
+use field::{Field, MontgomeryElement, FieldElement, FieldValue};
 
 #[derive(Copy, Clone, Debug, PartialEq)]
 struct P256Field;
@@ -15,47 +14,73 @@ impl field::Field for P256Field {
     type Value = U256;
 
     // 115792089210356248762697446949407573530086143415290314195533631308867097853951
-    const MODULUS: U256 = U256(bigint::U256(
+    const MODULUS: U256 = U256::from_raw(
         [
             0xffffffffffffffff,
             0x00000000ffffffff,
             0x0000000000000000,
             0xffffffff00000001,
         ]
-    ));
-
-    // 115792089237316195423570985008687907853269984665640564039457584007913129639936
-    const R: U256 = U256(bigint::U256(
-        [
-            0x0000000000000001,
-            0xFFFFFFFF00000000,
-            0xFFFFFFFFFFFFFFFF,
-            0x00000000FFFFFFFE,
-        ]
-    ));
+    );
+}
 
-    // 115792089183396302114378112356516095823261736990586219612555396166510339686400
-    const R_INVERSE: U256 = U256(bigint::U256(
-        [
-            0x0000000300000000,
-            0x00000001FFFFFFFE,
-            0xFFFFFFFD00000002,
-            0xFFFFFFFE00000003,
-        ]
-    ));
+impl field::MontgomeryField for P256Field {
+    const R: U256 = U256::from_raw(field::montgomery_r(Self::MODULUS.0));
+    const R_INVERSE: U256 = U256::from_raw(field::montgomery_r_inverse(Self::MODULUS.0));
+    const INV: u64 = field::montgomery_inv(Self::MODULUS.0);
 }
 
 impl P256Field {
+    #[allow(clippy::should_implement_trait)]
     pub fn from_str(v: &'static str) -> MontgomeryElement<Self> {
         U256::from(v).into()
     }
 }
 
+#[bench]
+fn regular(b: &mut test::Bencher) {
+    let mut val = FieldElement::<P256Field>::from(U256::from("11"));
+    let multiplyer = FieldElement::<P256Field>::from(U256::from("13")).pow(U256::from("55"));
+    b.iter(|| {
+        val *= multiplyer;
+    });
+}
+
 #[bench]
 fn simple(b: &mut test::Bencher) {
-    let mut val = MontgomeryElement::from(P256Field::from_str("11"));
-    let multiplyer = MontgomeryElement::from(P256Field::from_str("13")).pow(U256::from("55"));
+    let mut val = P256Field::from_str("11");
+    let multiplyer = P256Field::from_str("13").pow(U256::from("55"));
     b.iter(|| {
-        val = val * multiplyer;
+        val *= multiplyer;
     });
-}
\ No newline at end of file
+}
+
+#[bench]
+fn fermat_inverse(b: &mut test::Bencher) {
+    let val = P256Field::from_str("11");
+    let exponent = U256::from("115792089210356248762697446949407573530086143415290314195533631308867097853949");
+    b.iter(|| val.pow(exponent));
+}
+
+#[bench]
+fn fermat_inverse_vartime(b: &mut test::Bencher) {
+    let val = P256Field::from_str("11");
+    let exponent = U256::from("115792089210356248762697446949407573530086143415290314195533631308867097853949");
+    b.iter(|| val.pow_vartime(exponent));
+}
+
+#[bench]
+fn euclid_inverse(b: &mut test::Bencher) {
+    use field::ModInv;
+
+    let val = U256::from("55066263022277343669578718895168534326250603453777594175500187360389116729240");
+    b.iter(|| test::black_box(val).inv(P256Field::MODULUS));
+}
+
+#[bench]
+fn safegcd_inverse(b: &mut test::Bencher) {
+    use field::ModInvCt;
+
+    let val = U256::from("55066263022277343669578718895168534326250603453777594175500187360389116729240");
+    b.iter(|| test::black_box(val).inv_ct(P256Field::MODULUS));
+}
@@ -0,0 +1,185 @@
+//! dudect-style timing leakage test of the constant-time arithmetic
+//!
+//! Times every operation on two classes of inputs, a fixed one and random ones, interleaved
+//! in random order, and compares the timing distributions with Welch's t-test
+//! ("Dude, is my code constant time?", Reparaz, Balasch, Verbauwhede, 2017).
+//! |t| above 4.5 means the timing depends on the input. The variable-time Euclid
+//! inversion is included as a control, which is expected to leak. Slower operations,
+//! exponentiation and scalar multiplication, run a fraction of the measurements.
+//!
+//! Run with `cargo run --release -p ng-uint --example dudect [measurements]`.
+
+extern crate ng_uint as uint;
+extern crate ng_field as field;
+extern crate ng_curve as curve;
+extern crate rand;
+
+use std::hint::black_box;
+use std::time::Instant;
+
+use rand::Rng;
+use field::{ModInv, ModInvCt, ConstantTimeArith, ConstantTimeElement, FieldValue};
+use curve::{Curve, JacobianPoint};
+use uint::U256;
+
+/// Operation calls per timing, so that a measurement is well above the clock resolution
+const BATCH: usize = 16;
+
+/// Welch's t-test threshold used by dudect
+const THRESHOLD: f64 = 4.5;
+
+// secp256k1 modulus and -modulus^-1 mod 2^64
+const MODULUS: [u64; 4] = [0xFFFFFFFEFFFFFC2F, 0xFFFFFFFFFFFFFFFF, 0xFFFFFFFFFFFFFFFF, 0xFFFFFFFFFFFFFFFF];
+const INV: u64 = field::montgomery_inv(MODULUS);
+
+/// Operation under test, taking the input and a random second operand
+type Operation<T> = Box<dyn Fn(T, T) -> T>;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Secp256k1Field;
+
+impl field::Field for Secp256k1Field {
+    type Value = U256;
+    const MODULUS: U256 = U256::from_raw(MODULUS);
+}
+
+impl field::MontgomeryField for Secp256k1Field {
+    const R: U256 = U256::from_raw(field::montgomery_r(MODULUS));
+    const R_INVERSE: U256 = U256::from_raw(field::montgomery_r_inverse(MODULUS));
+    const INV: u64 = INV;
+}
+
+type Element = ConstantTimeElement<Secp256k1Field>;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Secp256k1Curve;
+
+/// y^2 = x^3 + 7
+impl Curve for Secp256k1Curve {
+    type Value = Element;
+
+    fn generator() -> curve::AffinePoint<Self> {
+        (
+            U256::from_hex("79BE667EF9DCBBAC55A06295CE870B07029BFCDB2DCE28D959F2815B16F81798"),
+            U256::from_hex("483ADA7726A3C4655DA4FBFC0E1108A8FD17B448A68554199C47D08FFB10D4B8"),
+        ).into()
+    }
+
+    fn a() -> Element {
+        Element::zero()
+    }
+}
+
+/// Online mean and variance (Welford)
+#[derive(Default)]
+struct Stats {
+    n: f64,
+    mean: f64,
+    m2: f64,
+}
+
+impl Stats {
+    fn push(&mut self, x: f64) {
+        self.n += 1.0;
+        let delta = x - self.mean;
+        self.mean += delta / self.n;
+        self.m2 += delta * (x - self.mean);
+    }
+
+    fn variance(&self) -> f64 {
+        self.m2 / (self.n - 1.0)
+    }
+}
+
+/// Welch's t statistic of two classes
+fn welch_t(a: &Stats, b: &Stats) -> f64 {
+    (a.mean - b.mean) / (a.variance() / a.n + b.variance() / b.n).sqrt()
+}
+
+/// Operand type, random inputs are generated ahead of the measurements
+trait Input: Copy {
+    fn sample<R: Rng>(rng: &mut R) -> Self;
+}
+
+impl Input for U256 {
+    fn sample<R: Rng>(rng: &mut R) -> Self {
+        let module = U256::from_raw(MODULUS);
+        U256::from_raw([rng.gen(), rng.gen(), rng.gen(), rng.gen()]) % module
+    }
+}
+
+impl Input for Element {
+    fn sample<R: Rng>(rng: &mut R) -> Self {
+        U256::sample(rng).into()
+    }
+}
+
+/// Measure `op` on the fixed (class 0) and random (class 1) inputs, returns |t|
+fn measure<R: Rng, T: Input, O: Fn(T, T) -> T>(rng: &mut R, measurements: usize, fixed: T, op: O) -> f64 {
+    let other = T::sample(rng);
+    let classes: Vec<bool> = (0..measurements).map(|_| rng.gen()).collect();
+    let inputs: Vec<T> = classes.iter().map(|&c| if c { T::sample(rng) } else { fixed }).collect();
+
+    let mut timings = Vec::with_capacity(measurements);
+    for &input in &inputs {
+        let start = Instant::now();
+        for _ in 0..BATCH {
+            black_box(op(black_box(input), black_box(other)));
+        }
+        timings.push(start.elapsed().as_nanos() as f64);
+    }
+
+    // crop interrupts and other outliers above the 95th percentile
+    let mut sorted = timings.clone();
+    sorted.sort_by(|a, b| a.partial_cmp(b).expect("timings are finite"));
+    let cutoff = sorted[sorted.len() * 95 / 100];
+
+    let (mut fixed_stats, mut random_stats) = (Stats::default(), Stats::default());
+    for (&class, &timing) in classes.iter().zip(timings.iter()) {
+        if timing > cutoff {
+            continue;
+        }
+        if class { random_stats.push(timing) } else { fixed_stats.push(timing) }
+    }
+
+    welch_t(&fixed_stats, &random_stats).abs()
+}
+
+fn main() {
+    let measurements = std::env::args().nth(1).map_or(100_000, |n| n.parse().expect("measurements should be a number"));
+    let mut rng = rand::thread_rng();
+    let module = U256::from_raw(MODULUS);
+    let (zero, one) = (U256::from_raw([0; 4]), U256::from_raw([1, 0, 0, 0]));
+
+    let base = Element::sample(&mut rng);
+    let generator: JacobianPoint<Secp256k1Curve> = Secp256k1Curve::generator().into();
+
+    // (name, fixed input, measurement divisor, operation)
+    let values: Vec<(&str, U256, usize, Operation<U256>)> = vec![
+        ("add_ct", zero, 1, Box::new(move |x, y| x.add_ct(y, module))),
+        ("sub_ct", zero, 1, Box::new(move |x, y| x.sub_ct(y, module))),
+        ("mul_reduce_ct", zero, 1, Box::new(move |x, y| x.mul_reduce_ct(y, module, INV))),
+        ("inv_ct", one, 1, Box::new(move |x, _| x.inv_ct(module))),
+        ("inv (euclid, control)", one, 1, Box::new(move |x, _| x.inv(module))),
+        ("element pow", zero, 10, Box::new(move |x, _| base.pow(x).into_value())),
+        ("mul_ct", zero, 100, Box::new(move |x, _| generator.clone().mul_ct(x).into_parts().0.into_value())),
+    ];
+
+    let elements: Vec<(&str, Element, usize, Operation<Element>)> = vec![
+        ("element add", Element::zero(), 1, Box::new(|x, y| x + y)),
+        ("element sub", Element::zero(), 1, Box::new(|x, y| x - y)),
+        ("element mul", Element::zero(), 1, Box::new(|x, y| x * y)),
+        ("element div", Element::one(), 1, Box::new(|x, y| y / x)),
+    ];
+
+    for (name, fixed, divisor, op) in values {
+        report(name, measure(&mut rng, measurements / divisor, fixed, op));
+    }
+    for (name, fixed, divisor, op) in elements {
+        report(name, measure(&mut rng, measurements / divisor, fixed, op));
+    }
+}
+
+fn report(name: &str, t: f64) {
+    println!("{:<24} |t| = {:>8.2}  {}", name, t, if t > THRESHOLD { "leaks" } else { "ok" });
+}
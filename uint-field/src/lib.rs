@@ -1,93 +1,40 @@
-extern crate bigint;
+//! Multi-limb unsigned integers as field values
+//!
+//! Arithmetic is implemented natively on 64-bit limbs, see [`Uint`](struct.Uint.html).
+//! The limb primitives `adc`, `sbb` and `mac` are exported for special-form field reductions.
+
+#[cfg(test)] extern crate bigint;
 extern crate ng_field as field;
 #[cfg(test)] #[macro_use] extern crate quickcheck;
+#[cfg(test)] extern crate rand;
+#[cfg(feature = "serde")] extern crate serde;
 
-use field::{MulReduce, ModMul, ModAdd, ModNeg, ModInv, Scalar};
-
-#[derive(Clone, Copy, Debug, PartialEq)]
-pub struct U256(pub bigint::U256);
-
-impl U256 {
-    pub fn from_raw(v: [u64; 4]) -> Self {
-        U256(bigint::U256(v))
-    }
-
-    pub fn from_hex(v: &'static str) -> Self {
-        use std::str::FromStr;
-        U256(bigint::U256::from_str(v).unwrap())
-    }
-}
-
-impl ModMul for U256 {
-    fn mul(self, other: Self, module: Self) -> Self {
-        U256(
-            (bigint::U512::from(self.0) * bigint::U512::from(other.0) % bigint::U512::from(module.0)).into()
-        )
-    }
-}
-
-impl ModAdd for U256 {
-    fn add(self, other: Self, module: Self) -> Self {
-        U256(
-            ((bigint::U512::from(self.0) + bigint::U512::from(other.0)) % bigint::U512::from(module.0)).into()
-        )
-    }
-}
-
-impl MulReduce for U256 {
-    fn mul_reduce(self, other: Self, module: Self, r_inverse: Self) -> Self {
-        self.mul(other, module).mul(r_inverse, module)
-    }
-}
+mod uint;
 
-impl ModNeg for U256 {
-    fn neg(self, module: Self) -> Self {
-        U256(module.0 - (self.0 % module.0))
-    }
-}
+pub use uint::{Uint, WideUint, U384, U512, U576};
 
-impl ModInv for U256 {
-    fn inv(self, module: Self) -> Self {
-        U256(self.0.mod_inverse(module.0))
-    }
-}
-
-impl ModMul<u32> for U256 {
-    fn mul(self, scalar: u32, module: Self) -> Self {
-        // todo: overflow?
-        self.mul(U256(scalar.into()), module)
-    }
-}
+/// 256-bit unsigned integer, values of secp256k1, P-256 and BN254 fields
+pub type U256 = Uint<4>;
 
-impl ::std::ops::Rem for U256 {
-    type Output = U256;
-    fn rem(self, other: Self) -> Self {
-        U256(self.0 % other.0)
-    }
+/// a + b + carry, returning (result, carry)
+#[inline(always)]
+pub fn adc(a: u64, b: u64, carry: u64) -> (u64, u64) {
+    let t = (a as u128) + (b as u128) + (carry as u128);
+    (t as u64, (t >> 64) as u64)
 }
 
-impl From<&'static str> for U256 {
-    fn from(s: &'static str) -> Self {
-        U256(bigint::U256::from_dec_str(s).unwrap())
-    }
+/// a - b - borrow, returning (result, borrow)
+#[inline(always)]
+pub fn sbb(a: u64, b: u64, borrow: u64) -> (u64, u64) {
+    let t = (a as u128).wrapping_sub((b as u128) + (borrow as u128));
+    (t as u64, (t >> 127) as u64)
 }
 
-impl Scalar for U256 {
-    fn one() -> Self {
-        U256(1u64.into())
-    }
-
-    fn zero() -> Self {
-        U256(0u64.into())
-    }
-
-    fn max_bits() -> usize {
-        256
-    }
-
-    fn bit(&self, position: usize) -> bool {
-        self.0.bit(position)
-    }
+/// a + b * c + carry, returning (result, carry)
+#[inline(always)]
+pub fn mac(a: u64, b: u64, c: u64, carry: u64) -> (u64, u64) {
+    let t = (a as u128) + (b as u128) * (c as u128) + (carry as u128);
+    (t as u64, (t >> 64) as u64)
 }
 
 #[cfg(test)]
@@ -96,45 +43,48 @@ mod tests {
     #[derive(Clone, Copy, PartialEq, Debug)]
     pub struct BtcField;
 
-    use quickcheck::{TestResult, Arbitrary, Gen};
+    use quickcheck::TestResult;
     use {bigint, field};
     use field::FieldElement;
     use super::U256;
 
-    impl Arbitrary for U256 {
-        fn arbitrary<G: Gen>(g: &mut G) -> Self {
-            U256::from_raw([u64::arbitrary(g), u64::arbitrary(g), u64::arbitrary(g), u64::arbitrary(g)])
-        }
-    }
-
     impl field::Field for BtcField {
         type Value = U256;
 
         // 115792089237316195423570985008687907853269984665640564039457584007908834671663
-        const MODULUS: U256 = U256(bigint::U256(
-            [
+        const MODULUS: U256 = U256::from_raw([
                 0xFFFFFFFEFFFFFC2F,
                 0xFFFFFFFFFFFFFFFF,
                 0xFFFFFFFFFFFFFFFF,
                 0xFFFFFFFFFFFFFFFF,
             ]
-        ));
+        );
+    }
 
-        // don't use it for montgomery form
-        const R: U256 = U256(bigint::U256([0, 0, 0, 0]));
-        const R_INVERSE: U256 = U256(bigint::U256([0, 0, 0, 0]));
+    impl field::BarrettField for BtcField {
+        const MU: U256 = U256::from_raw(field::barrett_mu(<Self as field::Field>::MODULUS.0));
     }
 
     impl BtcField {
+        #[allow(clippy::should_implement_trait)]
         fn from_str(v: &'static str) -> FieldElement<Self> {
             U256::from(v).into()
         }
 
         fn from_u64(x: u64) -> FieldElement<Self> {
-            U256(x.into()).into()
+            U256::from(x).into()
         }
     }
 
+    #[test]
+    fn neg_zero() {
+        use field::{Field, ModNeg};
+
+        let zero = U256::from("0");
+        assert_eq!(zero.neg(BtcField::MODULUS), zero);
+        assert_eq!(-BtcField::from_str("0"), BtcField::from_str("0"));
+    }
+
     #[test]
     fn field1() {
         let p1 = BtcField::from_str(
@@ -188,11 +138,121 @@ mod tests {
         )
     }
 
+    #[test]
+    fn dynamic_field_constants() {
+        use field::DynamicField;
+
+        // P-256
+        let field = DynamicField::new(
+            U256::from_raw([0xffffffffffffffff, 0x00000000ffffffff, 0x0000000000000000, 0xffffffff00000001])
+        ).unwrap();
+
+        assert_eq!(
+            field.r(),
+            U256::from_raw([0x0000000000000001, 0xFFFFFFFF00000000, 0xFFFFFFFFFFFFFFFF, 0x00000000FFFFFFFE])
+        );
+        assert_eq!(
+            field.r_inverse(),
+            U256::from_raw([0x0000000300000000, 0x00000001FFFFFFFE, 0xFFFFFFFD00000002, 0xFFFFFFFE00000003])
+        );
+        assert_eq!(field.inv(), 1);
+    }
+
+    #[test]
+    fn strings() {
+        use field::ParseError;
+
+        let p = "115792089237316195423570985008687907853269984665640564039457584007908834671663";
+        let p_hex = "0xfffffffffffffffffffffffffffffffffffffffffffffffffffffffefffffc2f";
+
+        assert_eq!(p.parse(), Ok(U256::from(p)));
+        assert_eq!(p_hex.parse(), Ok(U256::from(p)));
+        assert_eq!(format!("{}", U256::from(p)), p);
+        assert_eq!(format!("{:#x}", U256::from(p)), p_hex);
+        assert_eq!(format!("{:x}", U256::from_raw([0x1f, 0, 0, 0])), "1f");
+
+        // 2^256 does not fit
+        assert_eq!(
+            "115792089237316195423570985008687907853269984665640564039457584007913129639936".parse::<U256>(),
+            Err(ParseError::Overflow)
+        );
+        assert_eq!("12z".parse::<U256>(), Err(ParseError::InvalidDigit));
+
+        // field elements reject the modulus and print canonical values
+        assert_eq!(p.parse::<FieldElement<BtcField>>(), Err(ParseError::NotCanonical));
+        let minus_one: FieldElement<BtcField> = "115792089237316195423570985008687907853269984665640564039457584007908834671662".parse().unwrap();
+        assert_eq!(minus_one, -BtcField::from_u64(1));
+        assert_eq!(format!("{}", minus_one), "115792089237316195423570985008687907853269984665640564039457584007908834671662");
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde() {
+        extern crate serde_json;
+        extern crate ciborium;
+
+        let x = U256::from_raw([0x0123456789abcdef, 0, 0, 0xfedcba9876543210]);
+        let json = "\"fedcba9876543210000000000000000000000000000000000123456789abcdef\"";
+
+        assert_eq!(&serde_json::to_string(&x).unwrap(), json);
+        assert_eq!(serde_json::from_str::<U256>(json).unwrap(), x);
+        assert!(serde_json::from_str::<U256>("\"0123\"").is_err());
+
+        let mut cbor = Vec::new();
+        ciborium::ser::into_writer(&x, &mut cbor).unwrap();
+        assert_eq!(cbor.len(), 34);
+        assert_eq!(ciborium::de::from_reader::<U256, _>(&cbor[..]).unwrap(), x);
+    }
+
+    #[cfg(feature = "rand")]
+    #[test]
+    fn random() {
+        use rand::{SeedableRng, rngs::StdRng};
+        use field::{Field, FieldValue, Scalar};
+
+        let mut rng = StdRng::seed_from_u64(256);
+
+        // multi-limb bound, values are reduced and the top bits are exercised
+        let mut top = 0;
+        for _ in 0..1000 {
+            let x = FieldElement::<BtcField>::random(&mut rng).into_value();
+            assert_eq!(x % BtcField::MODULUS, x);
+            top |= x.0[3] >> 63;
+        }
+        assert_eq!(top, 1);
+
+        // bound in the second limb
+        let bound = U256::from_raw([0, 3, 0, 0]);
+        let mut counts = [0usize; 3];
+        for _ in 0..3000 {
+            let x = U256::random_below(&mut rng, bound);
+            counts[x.0[1] as usize] += 1;
+        }
+        assert!(counts.iter().all(|&c| c > 850 && c < 1150), "{:?}", counts);
+    }
+
     quickcheck! {
+        fn strings_roundtrip(x: U256) -> bool {
+            format!("{}", x).parse() == Ok(x) &&
+                format!("{:#x}", x).parse() == Ok(x) &&
+                format!("{}", x) == format!("{}", bigint::U256(x.0))
+        }
+
+        fn dynamic_equals_static(x: U256, y: U256) -> bool {
+            use field::{Field, DynamicField, DynamicElement};
+
+            let field = DynamicField::new(BtcField::MODULUS).unwrap();
+            let (x_d, y_d) = (DynamicElement::new(x, &field), DynamicElement::new(y, &field));
+            let (x_s, y_s): (FieldElement<BtcField>, FieldElement<BtcField>) = (x.into(), y.into());
+
+            (x_d.clone() * y_d.clone()).into_value() == (x_s * y_s).into_value() &&
+                (x_d - y_d).into_value() == (x_s - y_s).into_value()
+        }
+
         fn number_div_by_self_equals_one(x: U256) -> TestResult {
             use field::Field;
 
-            if x % BtcField::MODULUS == U256(0.into()) {
+            if x % BtcField::MODULUS == U256::from(0) {
                 TestResult::discard()
             } else {
                 let x_e: FieldElement<BtcField> = x.into();
@@ -204,7 +264,7 @@ mod tests {
         fn one_div_number_equals_inverse(x: U256) -> TestResult {
             use field::{Field, ModInv};
 
-            if x % BtcField::MODULUS == U256(0.into()) {
+            if x % BtcField::MODULUS == U256::from(0) {
                 TestResult::discard()
             } else {
                 let x_e: FieldElement<BtcField> = x.into();
@@ -215,5 +275,92 @@ mod tests {
                 )
             }
         }
+
+        fn jacobi_equals_legendre(x: U256) -> bool {
+            use field::{Field, FieldValue, Jacobi};
+
+            let x_e: FieldElement<BtcField> = x.into();
+            x.jacobi(BtcField::MODULUS) == x_e.legendre()
+        }
+
+        fn barrett_equals_regular(x: U256, y: U256) -> bool {
+            use field::{Field, BarrettField, BarrettElement, BarrettMul, ModMul};
+
+            let (x_b, y_b): (BarrettElement<BtcField>, BarrettElement<BtcField>) = (x.into(), y.into());
+            let (x_e, y_e): (FieldElement<BtcField>, FieldElement<BtcField>) = (x.into(), y.into());
+
+            // small modulus, 130 bits
+            let module = U256::from_raw([0xffffffffffffffc5, 0xffffffffffffffff, 0x3, 0]);
+            let mu = U256::from_raw(field::barrett_mu(module.0));
+            let (x_s, y_s) = (x % module, y % module);
+
+            (x_b * y_b).into_element() == x_e * y_e &&
+                x_s.mul_barrett(y_s, module, mu) == ModMul::mul(x_s, y_s, module) &&
+                <BtcField as BarrettField>::MU == U256::from_raw(field::barrett_mu(BtcField::MODULUS.0))
+        }
+
+        fn mul_reduce_is_montgomery_multiplication(x: U256, y: U256) -> bool {
+            use field::{ModMul, MulReduce};
+
+            // secp256k1 modulus with R = 2^256, R^-1 and -p^-1 mod 2^64
+            let module = U256::from_raw([0xFFFFFFFEFFFFFC2F, 0xFFFFFFFFFFFFFFFF, 0xFFFFFFFFFFFFFFFF, 0xFFFFFFFFFFFFFFFF]);
+            let r_inverse = U256::from_raw([0xd838091d0868192a, 0xbcb223fedc24a059, 0x9c46c2c295f2b761, 0xc9bd190515538399]);
+            let inv = 0xd838091dd2253531;
+
+            let (x, y) = (x % module, y % module);
+
+            x.mul_reduce(y, module, r_inverse, inv) == ModMul::mul(ModMul::mul(x, y, module), r_inverse, module)
+        }
+
+        fn mul_reduce_is_montgomery_multiplication_p256(x: U256, y: U256) -> bool {
+            use field::{ModMul, MulReduce};
+
+            let module = U256::from_raw([0xffffffffffffffff, 0x00000000ffffffff, 0x0000000000000000, 0xffffffff00000001]);
+            let r_inverse = U256::from_raw([0x0000000300000000, 0x00000001FFFFFFFE, 0xFFFFFFFD00000002, 0xFFFFFFFE00000003]);
+            let inv = 0x0000000000000001;
+
+            let (x, y) = (x % module, y % module);
+
+            x.mul_reduce(y, module, r_inverse, inv) == ModMul::mul(ModMul::mul(x, y, module), r_inverse, module)
+        }
+
+        fn safegcd_equals_euclid(x: U256) -> bool {
+            use field::{Field, ModInv, ModInvCt};
+
+            let secp256k1 = BtcField::MODULUS;
+            let p256 = U256::from_raw([0xffffffffffffffff, 0x00000000ffffffff, 0x0000000000000000, 0xffffffff00000001]);
+            let (x_k, x_p) = (x % secp256k1, x % p256);
+
+            x_k.inv_ct(secp256k1) == x_k.inv(secp256k1) && x_p.inv_ct(p256) == x_p.inv(p256)
+        }
+
+        fn constant_time_equals_regular(x: U256, y: U256) -> bool {
+            use field::{Field, ModAdd, ModMul, ModNeg, ModInv, ModInvCt, ConstantTimeArith, ConstantTimeEq};
+
+            // (module, R^-1, -module^-1 mod 2^64) of secp256k1 and P-256
+            let fields = [
+                (
+                    BtcField::MODULUS,
+                    U256::from_raw([0xd838091d0868192a, 0xbcb223fedc24a059, 0x9c46c2c295f2b761, 0xc9bd190515538399]),
+                    0xd838091dd2253531,
+                ),
+                (
+                    U256::from_raw([0xffffffffffffffff, 0x00000000ffffffff, 0x0000000000000000, 0xffffffff00000001]),
+                    U256::from_raw([0x0000000300000000, 0x00000001FFFFFFFE, 0xFFFFFFFD00000002, 0xFFFFFFFE00000003]),
+                    1,
+                ),
+            ];
+
+            fields.iter().all(|&(p, r_inverse, inv)| {
+                let (x, y) = (x % p, y % p);
+
+                x.add_ct(y, p) == ModAdd::add(x, y, p) &&
+                    x.sub_ct(y, p) == ModAdd::add(x, ModNeg::neg(y, p), p) &&
+                    x.mul_reduce_ct(y, p, inv) == ModMul::mul(ModMul::mul(x, y, p), r_inverse, p) &&
+                    x.inv_ct(p) == x.inv(p) &&
+                    bool::from(x.ct_eq(&y)) == (x == y) &&
+                    bool::from(x.ct_eq(&x))
+            })
+        }
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,854 @@
+//! Fixed-width multi-limb unsigned integers
+
+use std::cmp::Ordering;
+use std::ops::{Rem, Shr};
+
+use field::{MulReduce, BarrettMul, ModMul, ModAdd, ModNeg, ModInv, ModInvCt, Jacobi, Scalar, safegcd_inverse};
+use field::{Choice, ConditionallySelectable, ConstantTimeEq, ConstantTimeArith};
+use {adc, sbb, mac};
+
+/// Unsigned integer of LIMBS 64-bit limbs, least significant limb first
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Uint<const LIMBS: usize>(pub [u64; LIMBS]);
+
+/// 384-bit unsigned integer, values of P-384 and BLS12-381 fields
+pub type U384 = Uint<6>;
+
+/// 512-bit unsigned integer
+pub type U512 = Uint<8>;
+
+/// 576-bit unsigned integer, values of the P-521 field
+pub type U576 = Uint<9>;
+
+/// Double-width integer, product of two `Uint<LIMBS>`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct WideUint<const LIMBS: usize> {
+    /// Low LIMBS limbs
+    pub lo: Uint<LIMBS>,
+    /// High LIMBS limbs
+    pub hi: Uint<LIMBS>,
+}
+
+impl<const LIMBS: usize> Uint<LIMBS> {
+    /// New value from little-endian limbs
+    pub const fn from_raw(limbs: [u64; LIMBS]) -> Self {
+        Uint(limbs)
+    }
+
+    /// Value from hex digits without prefix, panics on invalid input (see `str::parse` for fallible parsing)
+    pub fn from_hex(v: &str) -> Self {
+        Scalar::parse(&["0x", v].concat()).expect("hex string of a value that fits the limbs")
+    }
+
+    /// Test if the value is zero
+    pub fn is_zero(&self) -> bool {
+        self.0.iter().all(|&limb| limb == 0)
+    }
+
+    /// Number of significant bits
+    pub fn bit_len(&self) -> usize {
+        self.0.iter().rposition(|&limb| limb != 0)
+            .map_or(0, |i| 64 * i + 64 - self.0[i].leading_zeros() as usize)
+    }
+
+    /// self + other + carry, returning the outgoing carry
+    pub fn carrying_add(self, other: Self, carry: u64) -> (Self, u64) {
+        let mut result = [0u64; LIMBS];
+        let mut carry = carry;
+        for (r, (&a, &b)) in result.iter_mut().zip(self.0.iter().zip(other.0.iter())) {
+            let (v, c) = adc(a, b, carry);
+            *r = v;
+            carry = c;
+        }
+        (Uint(result), carry)
+    }
+
+    /// self - other - borrow, wrapping, returning the outgoing borrow
+    pub fn borrowing_sub(self, other: Self, borrow: u64) -> (Self, u64) {
+        let mut result = [0u64; LIMBS];
+        let mut borrow = borrow;
+        for (r, (&a, &b)) in result.iter_mut().zip(self.0.iter().zip(other.0.iter())) {
+            let (v, bo) = sbb(a, b, borrow);
+            *r = v;
+            borrow = bo;
+        }
+        (Uint(result), borrow)
+    }
+
+    /// Full product, schoolbook multiplication for short values and Karatsuba for long ones
+    pub fn widening_mul(self, other: Self) -> WideUint<LIMBS> {
+        let mut product = [[0u64; LIMBS]; 2];
+        mul_into::<LIMBS>(&self.0, &other.0, product.as_flattened_mut());
+        WideUint { lo: Uint(product[0]), hi: Uint(product[1]) }
+    }
+
+    /// Quotient and remainder, panics when other is zero
+    pub fn div_rem(self, other: Self) -> (Self, Self) {
+        let mut quotient = [0u64; LIMBS];
+        let remainder = div_rem(&self.0, &other.0, Some(&mut quotient));
+        (Uint(quotient), Uint(remainder))
+    }
+}
+
+impl<const LIMBS: usize> Shr<usize> for Uint<LIMBS> {
+    type Output = Self;
+    fn shr(self, bits: usize) -> Self {
+        let mut result = [0u64; LIMBS];
+        shr_into(&self.0, bits, &mut result);
+        Uint(result)
+    }
+}
+
+impl<const LIMBS: usize> Shr<usize> for WideUint<LIMBS> {
+    type Output = Self;
+    fn shr(self, bits: usize) -> Self {
+        let mut result = [[0u64; LIMBS]; 2];
+        shr_into([self.lo.0, self.hi.0].as_flattened(), bits, result.as_flattened_mut());
+        WideUint { lo: Uint(result[0]), hi: Uint(result[1]) }
+    }
+}
+
+/// Operand length from which Karatsuba multiplication is used
+///
+/// Measured with the `multiplication` benches: field sized values are faster with schoolbook.
+/// `U256`, `U384` and `U576` (4, 6 and 9 limbs) never reach it and always use schoolbook,
+/// Karatsuba only serves wider `Uint`s.
+const KARATSUBA_THRESHOLD: usize = 32;
+
+/// out = a * b, operands of the same length (at most LIMBS), out is twice as long
+fn mul_into<const LIMBS: usize>(a: &[u64], b: &[u64], out: &mut [u64]) {
+    let n = a.len();
+    if n < KARATSUBA_THRESHOLD {
+        schoolbook_mul(a, b, out);
+        return;
+    }
+
+    // a = a1 * B^h + a0, b = b1 * B^h + b0, B = 2^64:
+    // a * b = z2 * B^(2h) + z1 * B^h + z0, z1 = (a1 + a0)(b1 + b0) - z2 - z0
+    let h = n / 2;
+    let m = n - h;
+    let (a0, a1) = a.split_at(h);
+    let (b0, b1) = b.split_at(h);
+
+    {
+        let (z0, z2) = out.split_at_mut(2 * h);
+        mul_into::<LIMBS>(a0, b0, z0);
+        mul_into::<LIMBS>(a1, b1, z2);
+    }
+
+    // m + 1 <= LIMBS, so sums and their product fit 4 * LIMBS words
+    let mut scratch = [[0u64; LIMBS]; 4];
+    let (sum_a, rest) = scratch.as_flattened_mut().split_at_mut(m + 1);
+    let (sum_b, rest) = rest.split_at_mut(m + 1);
+    let z1 = &mut rest[..2 * (m + 1)];
+
+    sum_a[..m].copy_from_slice(a1);
+    sum_a[m] = add_assign(&mut sum_a[..m], a0);
+    sum_b[..m].copy_from_slice(b1);
+    sum_b[m] = add_assign(&mut sum_b[..m], b0);
+    mul_into::<LIMBS>(sum_a, sum_b, z1);
+
+    // z1 = a1 * b0 + a0 * b1 is not negative, and 2 * (m + 1) <= n + m words fit above B^h
+    sub_assign(z1, &out[..2 * h]);
+    sub_assign(z1, &out[2 * h..]);
+    add_assign(&mut out[h..], z1);
+}
+
+/// out = a * b, operands of the same length, out is twice as long
+#[inline(always)]
+fn schoolbook_mul(a: &[u64], b: &[u64], out: &mut [u64]) {
+    let n = a.len();
+    out.fill(0);
+
+    for (i, &a) in a.iter().enumerate() {
+        let mut carry = 0;
+        for (j, &b) in b.iter().enumerate() {
+            let (v, c) = mac(out[i + j], a, b, carry);
+            out[i + j] = v;
+            carry = c;
+        }
+        out[i + n] = carry;
+    }
+}
+
+/// a += b, b is not longer than a, returns the carry out of a
+fn add_assign(a: &mut [u64], b: &[u64]) -> u64 {
+    let mut carry = 0;
+    for (i, a) in a.iter_mut().enumerate() {
+        let (v, c) = adc(*a, b.get(i).copied().unwrap_or(0), carry);
+        *a = v;
+        carry = c;
+    }
+    carry
+}
+
+/// a -= b, b is not longer than a, returns the borrow out of a
+fn sub_assign(a: &mut [u64], b: &[u64]) -> u64 {
+    let mut borrow = 0;
+    for (i, a) in a.iter_mut().enumerate() {
+        let (v, bo) = sbb(*a, b.get(i).copied().unwrap_or(0), borrow);
+        *a = v;
+        borrow = bo;
+    }
+    borrow
+}
+
+/// dst = src >> bits, truncated to the length of dst
+fn shr_into(src: &[u64], bits: usize, dst: &mut [u64]) {
+    let (limbs, bits) = (bits / 64, bits % 64);
+    for (i, d) in dst.iter_mut().enumerate() {
+        let low = src.get(i + limbs).map_or(0, |&limb| limb >> bits);
+        let high = match src.get(i + limbs + 1) {
+            Some(&limb) if bits != 0 => limb << (64 - bits),
+            _ => 0,
+        };
+        *d = low | high;
+    }
+}
+
+/// Long division of `num` (at most 2 * LIMBS limbs) by `den` (Knuth, algorithm D)
+///
+/// The quotient is written to `quotient` when given, it should hold `num.len()` limbs
+/// or less if the quotient is known to be shorter. Returns the remainder.
+fn div_rem<const LIMBS: usize>(num: &[u64], den: &[u64; LIMBS], mut quotient: Option<&mut [u64]>) -> [u64; LIMBS] {
+    let n = den.iter().rposition(|&limb| limb != 0).map_or(0, |i| i + 1);
+    assert!(n != 0, "division by zero");
+
+    let mut remainder = [0u64; LIMBS];
+
+    if num.len() < n {
+        remainder[..num.len()].copy_from_slice(num);
+        return remainder;
+    }
+
+    if n == 1 {
+        // short division by a single limb
+        let d = den[0] as u128;
+        let mut r = 0u128;
+        for (i, &limb) in num.iter().enumerate().rev() {
+            let t = (r << 64) | limb as u128;
+            if let Some(q) = quotient.as_mut().and_then(|q| q.get_mut(i)) {
+                *q = (t / d) as u64;
+            }
+            r = t % d;
+        }
+        remainder[0] = r as u64;
+        return remainder;
+    }
+
+    // normalize, so that the top limb of the divisor has its top bit set
+    let shift = den[n - 1].leading_zeros();
+    let mut v = [0u64; LIMBS];
+    shl_into(&den[..n], shift, &mut v[..n]);
+
+    let mut scratch = [[0u64; LIMBS]; 3];
+    let u = &mut scratch.as_flattened_mut()[..num.len() + 1];
+    shl_into(num, shift, u);
+
+    let (v_top, v_next) = (v[n - 1] as u128, v[n - 2] as u128);
+    for j in (0..=num.len() - n).rev() {
+        // estimate the quotient limb from the top two limbs, it is at most 2 above the exact one
+        let top = ((u[j + n] as u128) << 64) | u[j + n - 1] as u128;
+        let (mut q, mut r) = (top / v_top, top % v_top);
+        while q >> 64 != 0 || q * v_next > ((r << 64) | u[j + n - 2] as u128) {
+            q -= 1;
+            r += v_top;
+            if r >> 64 != 0 {
+                break;
+            }
+        }
+
+        // u[j..j + n + 1] -= q * v
+        let (mut carry, mut borrow) = (0u64, 0u64);
+        for i in 0..n {
+            let p = (q as u64 as u128) * (v[i] as u128) + carry as u128;
+            carry = (p >> 64) as u64;
+            let (t, b) = sbb(u[i + j], p as u64, borrow);
+            u[i + j] = t;
+            borrow = b;
+        }
+        let (t, b) = sbb(u[j + n], carry, borrow);
+        u[j + n] = t;
+
+        // estimate was one too large, add the divisor back
+        let mut q = q as u64;
+        if b != 0 {
+            q -= 1;
+            let mut carry = 0;
+            for i in 0..n {
+                let (t, c) = adc(u[i + j], v[i], carry);
+                u[i + j] = t;
+                carry = c;
+            }
+            u[j + n] = u[j + n].wrapping_add(carry);
+        }
+
+        if let Some(quotient) = quotient.as_mut().and_then(|q| q.get_mut(j)) {
+            *quotient = q;
+        }
+    }
+
+    shr_into(&u[..n], shift as usize, &mut remainder[..n]);
+    remainder
+}
+
+/// dst = src << bits, bits < 64, dst is at least as long as src and takes the outgoing bits
+fn shl_into(src: &[u64], bits: u32, dst: &mut [u64]) {
+    let mut carry = 0;
+    for (d, &limb) in dst.iter_mut().zip(src.iter()) {
+        *d = (limb << bits) | carry;
+        carry = if bits == 0 { 0 } else { limb >> (64 - bits) };
+    }
+    if let Some(d) = dst.get_mut(src.len()) {
+        *d = carry;
+    }
+}
+
+impl<const LIMBS: usize> PartialOrd for Uint<LIMBS> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<const LIMBS: usize> Ord for Uint<LIMBS> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.iter().rev().cmp(other.0.iter().rev())
+    }
+}
+
+impl<const LIMBS: usize> From<u64> for Uint<LIMBS> {
+    fn from(value: u64) -> Self {
+        let mut limbs = [0u64; LIMBS];
+        limbs[0] = value;
+        Uint(limbs)
+    }
+}
+
+/// Value from decimal digits, panics on invalid input (see `str::parse` for fallible parsing)
+impl<const LIMBS: usize> From<&'static str> for Uint<LIMBS> {
+    fn from(s: &'static str) -> Self {
+        s.parse().expect("decimal string of a value that fits the limbs")
+    }
+}
+
+impl<const LIMBS: usize> Rem for Uint<LIMBS> {
+    type Output = Self;
+    fn rem(self, other: Self) -> Self {
+        Uint(div_rem(&self.0, &other.0, None))
+    }
+}
+
+impl<const LIMBS: usize> Rem<Uint<LIMBS>> for WideUint<LIMBS> {
+    type Output = Uint<LIMBS>;
+    fn rem(self, other: Uint<LIMBS>) -> Uint<LIMBS> {
+        Uint(div_rem([self.lo.0, self.hi.0].as_flattened(), &other.0, None))
+    }
+}
+
+impl<const LIMBS: usize> ModAdd for Uint<LIMBS> {
+    fn add(self, other: Self, module: Self) -> Self {
+        if self >= module || other >= module {
+            return (self % module).add(other % module, module);
+        }
+
+        let (sum, carry) = self.carrying_add(other, 0);
+        let (reduced, borrow) = sum.borrowing_sub(module, 0);
+        if carry == 1 || borrow == 0 { reduced } else { sum }
+    }
+}
+
+impl<const LIMBS: usize> ModMul for Uint<LIMBS> {
+    fn mul(self, other: Self, module: Self) -> Self {
+        self.widening_mul(other) % module
+    }
+}
+
+impl<const LIMBS: usize> ModMul<u32> for Uint<LIMBS> {
+    fn mul(self, scalar: u32, module: Self) -> Self {
+        self.widening_mul(Uint::from(u64::from(scalar))) % module
+    }
+}
+
+impl<const LIMBS: usize> ModNeg for Uint<LIMBS> {
+    fn neg(self, module: Self) -> Self {
+        let r = self % module;
+        if r.is_zero() { r } else { module.borrowing_sub(r, 0).0 }
+    }
+}
+
+impl<const LIMBS: usize> ModInv for Uint<LIMBS> {
+    fn inv(self, module: Self) -> Self {
+        let mut mn = (module, self);
+
+        // Bezout coefficients alternate in sign and do not exceed the module in magnitude,
+        // so only magnitudes are kept: |x_{i+1}| = |x_{i-1}| + q * |x_i| without reduction
+        let mut xy = (Self::zero(), Self::one());
+        let mut negative = true;
+
+        while !mn.1.is_zero() {
+            let (q, r) = mn.0.div_rem(mn.1);
+            xy = (xy.1, xy.0.carrying_add(q.widening_mul(xy.1).lo, 0).0);
+            mn = (mn.1, r);
+            negative = !negative;
+        }
+
+        if negative && !xy.0.is_zero() { module.borrowing_sub(xy.0, 0).0 } else { xy.0 }
+    }
+}
+
+impl<const LIMBS: usize> ModInvCt for Uint<LIMBS> {
+    fn inv_ct(self, module: Self) -> Self {
+        Uint(safegcd_inverse(self.0, module.0))
+    }
+}
+
+impl<const LIMBS: usize> MulReduce for Uint<LIMBS> {
+    // Montgomery multiplication (CIOS), R = 2^(64 * LIMBS)
+    fn mul_reduce(self, other: Self, module: Self, _r_inverse: Self, inv: u64) -> Self {
+        let (a, b, m) = (self.0, other.0, module.0);
+
+        // t holds LIMBS + 2 words: LIMBS limbs, `hi` and `top`
+        let mut t = [0u64; LIMBS];
+        let mut hi = 0;
+
+        for &b_i in b.iter() {
+            // t += a * b_i
+            let mut carry = 0;
+            for (t_j, &a_j) in t.iter_mut().zip(a.iter()) {
+                let (v, c) = mac(*t_j, a_j, b_i, carry);
+                *t_j = v;
+                carry = c;
+            }
+            let (v, top) = adc(hi, carry, 0);
+            hi = v;
+
+            // t = (t + k * m) / 2^64, where k is chosen so that the lowest word vanishes
+            let k = t[0].wrapping_mul(inv);
+            let (_, mut carry) = mac(t[0], k, m[0], 0);
+            for j in 1..LIMBS {
+                let (v, c) = mac(t[j], k, m[j], carry);
+                t[j - 1] = v;
+                carry = c;
+            }
+            let (v, c) = adc(hi, carry, 0);
+            t[LIMBS - 1] = v;
+            hi = top + c;
+        }
+
+        // t < 2 * module, final conditional subtraction without branching
+        let (r, borrow) = Uint(t).borrowing_sub(module, 0);
+        let keep = hi.ct_eq(&0) & Choice::from(borrow as u8);
+        Uint(<[u64; LIMBS]>::conditional_select(&r.0, &t, keep))
+    }
+}
+
+impl<const LIMBS: usize> BarrettMul for Uint<LIMBS> {
+    fn mul_barrett(self, other: Self, module: Self, mu: Self) -> Self {
+        let k = module.bit_len();
+        let x = self.widening_mul(other);
+
+        // x < module^2 < 2^(2k), so x1 fits the limbs, and so does q, which does not
+        // exceed the exact quotient
+        let x1 = (x >> k).lo;
+        let q = x1.carrying_add((x1.widening_mul(mu) >> k).lo, 0).0;
+
+        // r = x - q * module < 4 * module, kept in LIMBS + 1 limbs
+        let qm = q.widening_mul(module);
+        let (mut r, borrow) = x.lo.borrowing_sub(qm.lo, 0);
+        let mut r_hi = x.hi.0[0].wrapping_sub(qm.hi.0[0]).wrapping_sub(borrow);
+
+        while r_hi != 0 || r >= module {
+            let (v, borrow) = r.borrowing_sub(module, 0);
+            r = v;
+            r_hi -= borrow;
+        }
+        r
+    }
+}
+
+impl<const LIMBS: usize> ConditionallySelectable for Uint<LIMBS> {
+    fn conditional_select(a: &Self, b: &Self, choice: Choice) -> Self {
+        Uint(<[u64; LIMBS]>::conditional_select(&a.0, &b.0, choice))
+    }
+}
+
+impl<const LIMBS: usize> ConstantTimeEq for Uint<LIMBS> {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.0.ct_eq(&other.0)
+    }
+}
+
+impl<const LIMBS: usize> ConstantTimeArith for Uint<LIMBS> {
+    fn add_ct(self, other: Self, module: Self) -> Self {
+        let (sum, carry) = self.carrying_add(other, 0);
+        let (reduced, borrow) = sum.borrowing_sub(module, 0);
+
+        // keep the sum only when it neither wrapped nor reached the modulus
+        let keep = Choice::from(borrow as u8) & !Choice::from(carry as u8);
+        Self::conditional_select(&reduced, &sum, keep)
+    }
+
+    fn sub_ct(self, other: Self, module: Self) -> Self {
+        let (diff, borrow) = self.borrowing_sub(other, 0);
+
+        // add the modulus back when wrapped below zero
+        let mut masked = module;
+        for limb in masked.0.iter_mut() {
+            *limb &= borrow.wrapping_neg();
+        }
+        diff.carrying_add(masked, 0).0
+    }
+
+    fn mul_reduce_ct(self, other: Self, module: Self, inv: u64) -> Self {
+        // word-by-word montgomery reduction does not use R^-1
+        self.mul_reduce(other, module, Scalar::zero(), inv)
+    }
+}
+
+impl<const LIMBS: usize> Jacobi for Uint<LIMBS> {
+    fn jacobi(self, module: Self) -> i8 {
+        let mut a = self % module;
+        let mut n = module;
+        let mut t = 1;
+
+        while !a.is_zero() {
+            while a.0[0] & 1 == 0 {
+                a = a >> 1;
+                // (2 / n) = -1 when n = 3, 5 mod 8
+                let n8 = n.0[0] & 7;
+                if n8 == 3 || n8 == 5 { t = -t; }
+            }
+
+            if a < n {
+                ::std::mem::swap(&mut a, &mut n);
+                // quadratic reciprocity
+                if a.0[0] & 3 == 3 && n.0[0] & 3 == 3 { t = -t; }
+            }
+
+            a = a.borrowing_sub(n, 0).0;
+        }
+
+        if n == Self::one() { t } else { 0 }
+    }
+}
+
+impl<const LIMBS: usize> ::std::str::FromStr for Uint<LIMBS> {
+    type Err = field::ParseError;
+
+    /// Parse decimal or `0x`-prefixed hex string
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Scalar::parse(s)
+    }
+}
+
+impl<const LIMBS: usize> ::std::fmt::Display for Uint<LIMBS> {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        self.fmt_decimal(f)
+    }
+}
+
+impl<const LIMBS: usize> ::std::fmt::LowerHex for Uint<LIMBS> {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        self.fmt_lower_hex(f)
+    }
+}
+
+impl<const LIMBS: usize> Scalar for Uint<LIMBS> {
+    fn one() -> Self {
+        Uint::from(1)
+    }
+
+    fn zero() -> Self {
+        Uint([0; LIMBS])
+    }
+
+    fn max_bits() -> usize {
+        64 * LIMBS
+    }
+
+    fn bit(&self, position: usize) -> bool {
+        self.0.get(position / 64).is_some_and(|limb| (limb >> (position % 64)) & 1 == 1)
+    }
+
+    fn write_be_bytes(&self, bytes: &mut [u8]) {
+        for (chunk, limb) in bytes.chunks_mut(8).zip(self.0.iter().rev()) {
+            chunk.copy_from_slice(&limb.to_be_bytes());
+        }
+    }
+
+    fn read_be_bytes(bytes: &[u8]) -> Self {
+        let mut limbs = [0u64; LIMBS];
+        for (limb, chunk) in limbs.iter_mut().rev().zip(bytes.chunks(8)) {
+            let mut word = [0u8; 8];
+            word.copy_from_slice(chunk);
+            *limb = u64::from_be_bytes(word);
+        }
+        Uint(limbs)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<const LIMBS: usize> serde::Serialize for Uint<LIMBS> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut bytes = vec![0u8; 8 * LIMBS];
+        self.write_be_bytes(&mut bytes);
+        field::serialize_fixed_bytes(&bytes, serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, const LIMBS: usize> serde::Deserialize<'de> for Uint<LIMBS> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        field::deserialize_fixed_bytes(deserializer, 8 * LIMBS).map(|bytes| Uint::read_be_bytes(&bytes))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use quickcheck::{Arbitrary, Gen, TestResult};
+    use {bigint, field};
+    use field::{Field, FieldValue, FieldElement, MontgomeryElement, BarrettElement, ConstantTimeElement};
+    use field::{ModAdd, ModMul, ModNeg, ModInv, ModInvCt, Jacobi, Scalar};
+    use super::{Uint, U384, U576};
+    use U256;
+
+    impl<const LIMBS: usize> Arbitrary for Uint<LIMBS> {
+        fn arbitrary<G: Gen>(g: &mut G) -> Self {
+            // full-width limbs, zero and all-ones ones exercise carries and normalization
+            let mut limbs = [0u64; LIMBS];
+            for limb in limbs.iter_mut() {
+                *limb = match g.next_u32() % 4 {
+                    0 => 0,
+                    1 => u64::MAX,
+                    _ => g.next_u64(),
+                };
+            }
+            Uint(limbs)
+        }
+    }
+
+    macro_rules! test_field {
+        ($name: ident, $value: ty, $modulus: expr) => {
+            #[derive(Clone, Copy, PartialEq, Debug)]
+            struct $name;
+
+            impl field::Field for $name {
+                type Value = $value;
+                const MODULUS: $value = Uint($modulus);
+            }
+
+            impl field::MontgomeryField for $name {
+                const R: $value = Uint(field::montgomery_r($modulus));
+                const R_INVERSE: $value = Uint(field::montgomery_r_inverse($modulus));
+                const INV: u64 = field::montgomery_inv($modulus);
+            }
+
+            impl field::BarrettField for $name {
+                const MU: $value = Uint(field::barrett_mu($modulus));
+            }
+        }
+    }
+
+    // 2^256 - 2^32 - 977
+    test_field!(Secp256k1Field, U256, [
+        0xFFFFFFFEFFFFFC2F, 0xFFFFFFFFFFFFFFFF, 0xFFFFFFFFFFFFFFFF, 0xFFFFFFFFFFFFFFFF,
+    ]);
+
+    // 2^384 - 2^128 - 2^96 + 2^32 - 1
+    test_field!(P384Field, U384, [
+        0x00000000ffffffff, 0xffffffff00000000, 0xfffffffffffffffe,
+        0xffffffffffffffff, 0xffffffffffffffff, 0xffffffffffffffff,
+    ]);
+
+    // 2^521 - 1
+    test_field!(P521Field, U576, [
+        0xffffffffffffffff, 0xffffffffffffffff, 0xffffffffffffffff,
+        0xffffffffffffffff, 0xffffffffffffffff, 0xffffffffffffffff,
+        0xffffffffffffffff, 0xffffffffffffffff, 0x00000000000001ff,
+    ]);
+
+    // BLS12-381 base field
+    test_field!(Bls12381Field, U384, [
+        0xb9feffffffffaaab, 0x1eabfffeb153ffff, 0x6730d2a0f6b0f624,
+        0x64774b84f38512bf, 0x4b1ba7b6434bacd7, 0x1a0111ea397fe69a,
+    ]);
+
+    /// y^2 = x^3 - 3x + b
+    fn on_curve_a3<F: Field>(x: &str, y: &str, b: &str) -> bool {
+        let (x, y, b): (FieldElement<F>, FieldElement<F>, FieldElement<F>) =
+            (x.parse().unwrap(), y.parse().unwrap(), b.parse().unwrap());
+        y.squared() == x.squared() * x - x * 3 + b
+    }
+
+    /// Euler's criterion x^((p - 1) / 2) of the prime field F, as -1, 0 or 1
+    fn euler<F: Field<Value=Uint<LIMBS>>, const LIMBS: usize>(x: Uint<LIMBS>) -> i8 {
+        let power = FieldElement::<F>::from(x).pow_vartime(F::MODULUS >> 1);
+
+        if power == FieldElement::zero() { 0 } else if power == FieldElement::one() { 1 } else { -1 }
+    }
+
+    #[test]
+    fn smoky() {
+        let x = U384::from(u64::MAX);
+
+        assert_eq!(x.carrying_add(Uint::from(1), 0), (Uint::from_raw([0, 1, 0, 0, 0, 0]), 0));
+        assert_eq!(U384::zero().borrowing_sub(Uint::from(1), 0), (Uint([u64::MAX; 6]), 1));
+        assert_eq!(x.widening_mul(x).lo, Uint::from_raw([1, u64::MAX - 1, 0, 0, 0, 0]));
+        assert_eq!(Uint([u64::MAX; 6]).widening_mul(Uint([u64::MAX; 6])).hi, Uint([u64::MAX - 1, u64::MAX, u64::MAX, u64::MAX, u64::MAX, u64::MAX]));
+        assert_eq!(Uint::from_raw([0, 0, 1, 0, 0, 0]).div_rem(x), (Uint::from_raw([1, 1, 0, 0, 0, 0]), Uint::from(1)));
+        assert_eq!(x.bit_len(), 64);
+        assert_eq!(U384::zero().bit_len(), 0);
+        assert!(Uint::from_raw([0, 1, 0, 0, 0, 0]) > x);
+        assert_eq!(format!("{:x}", U576::from_raw([0, 0, 0, 0, 0, 0, 0, 0, 1])), format!("1{}", "0".repeat(128)));
+        assert_eq!("18446744073709551615".parse(), Ok(x));
+    }
+
+    #[test]
+    #[should_panic(expected = "division by zero")]
+    fn division_by_zero() {
+        let _ = U384::one() % U384::zero();
+    }
+
+    #[test]
+    fn p384() {
+        assert!(on_curve_a3::<P384Field>(
+            "0xaa87ca22be8b05378eb1c71ef320ad746e1d3b628ba79b9859f741e082542a385502f25dbf55296c3a545e3872760ab7",
+            "0x3617de4a96262c6f5d9e98bf9292dc29f8f41dbd289a147ce9da3113b5f0b8c00a60b1ce1d7e819d7a431d7c90ea0e5f",
+            "0xb3312fa7e23ee7e4988e056be3f82d19181d9c6efe8141120314088f5013875ac656398d8a2ed19d2a85c8edd3ec2aef",
+        ));
+    }
+
+    #[test]
+    fn p521() {
+        assert!(on_curve_a3::<P521Field>(
+            "0xc6858e06b70404e9cd9e3ecb662395b4429c648139053fb521f828af606b4d3dbaa14b5e77efe75928fe1dc127a2ffa8de3348b3c1856a429bf97e7e31c2e5bd66",
+            "0x11839296a789a3bc0045c8a5fb42c7d1bd998f54449579b446817afbd17273e662c97ee72995ef42640c550b9013fad0761353c7086a272c24088be94769fd16650",
+            "0x51953eb9618e1c9a1f929a21a0b68540eea2da725b99b315f3b8b489918ef109e156193951ec7e937b1652c0bd3bb1bf073573df883d2c34f1ef451fd46b503f00",
+        ));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde() {
+        extern crate serde_json;
+
+        let x = U384::from_raw([0x0123456789abcdef, 0, 0, 0, 0, 0xfedcba9876543210]);
+        let json = serde_json::to_string(&x).unwrap();
+
+        assert_eq!(json.len(), 2 + 96);
+        assert_eq!(serde_json::from_str::<U384>(&json).unwrap(), x);
+    }
+
+    quickcheck! {
+        fn equals_bigint(x: U256, y: U256, m: U256) -> TestResult {
+            if m.is_zero() {
+                return TestResult::discard();
+            }
+
+            let widen = |v: U256| bigint::U512::from(bigint::U256(v.0));
+            let low = |v: bigint::U512| [v.0[0], v.0[1], v.0[2], v.0[3]];
+            let (x_b, y_b, m_b) = (widen(x), widen(y), widen(m));
+
+            let wide = x.widening_mul(y);
+            let (q, r) = x.div_rem(m);
+
+            // prime modulus, for inversion and the Jacobi symbol
+            let p = Secp256k1Field::MODULUS;
+            let inverse = bigint::U256((x % p).0).mod_inverse(bigint::U256(p.0));
+
+            TestResult::from_bool(
+                [wide.lo.0, wide.hi.0].concat() == (x_b * y_b).0 &&
+                    q.0 == low(x_b / m_b) &&
+                    r.0 == low(x_b % m_b) &&
+                    ModAdd::add(x, y, m).0 == low((x_b + y_b) % m_b) &&
+                    ModMul::mul(x, y, m).0 == low(x_b * y_b % m_b) &&
+                    ModMul::mul(x, 7u32, m).0 == low(x_b * bigint::U512::from(7u64) % m_b) &&
+                    ModNeg::neg(x, m).0 == low((m_b - x_b % m_b) % m_b) &&
+                    x.inv(p).0 == inverse.0 &&
+                    x.jacobi(p) == euler::<Secp256k1Field, 4>(x)
+            )
+        }
+
+        fn karatsuba_equals_schoolbook(x: Uint<32>, y: Uint<32>, a: Uint<64>, b: Uint<64>) -> bool {
+            let (mut expected, mut expected_long) = ([0u64; 64], [0u64; 128]);
+            super::schoolbook_mul(&x.0, &y.0, &mut expected);
+            super::schoolbook_mul(&a.0, &b.0, &mut expected_long);
+
+            let (wide, wide_long) = (x.widening_mul(y), a.widening_mul(b));
+
+            [wide.lo.0, wide.hi.0].concat() == expected &&
+                [wide_long.lo.0, wide_long.hi.0].concat() == expected_long
+        }
+
+        fn wide_rem_equals_bigint(x: Uint<8>, y: Uint<8>) -> TestResult {
+            if y.is_zero() {
+                return TestResult::discard();
+            }
+
+            let (x_b, y_b) = (bigint::U512(x.0), bigint::U512(y.0));
+            let (q, r) = x.div_rem(y);
+
+            // top half of y as the divisor of a double-width dividend
+            let m = Uint([y.0[4], y.0[5], y.0[6], y.0[7]]);
+            let wide = super::WideUint { lo: Uint([x.0[0], x.0[1], x.0[2], x.0[3]]), hi: Uint([x.0[4], x.0[5], x.0[6], x.0[7]]) };
+            let m_b = bigint::U512([m.0[0], m.0[1], m.0[2], m.0[3], 0, 0, 0, 0]);
+
+            TestResult::from_bool(
+                q.0 == (x_b / y_b).0 &&
+                    r.0 == (x_b % y_b).0 &&
+                    (m.is_zero() || (wide % m).0 == (x_b % m_b).0[..4])
+            )
+        }
+
+        fn inverse_and_sqrt(x: U384) -> TestResult {
+            let p = Bls12381Field::MODULUS;
+            let x = x % p;
+            if x.is_zero() {
+                return TestResult::discard();
+            }
+
+            let x_e: FieldElement<Bls12381Field> = x.into();
+            let square = x_e.squared();
+            let root = square.sqrt().unwrap();
+
+            TestResult::from_bool(
+                ModMul::mul(x.inv(p), x, p) == U384::one() &&
+                    x.inv_ct(p) == x.inv(p) &&
+                    (root == x_e || root == -x_e) &&
+                    square.legendre() == 1
+            )
+        }
+
+        fn montgomery_and_barrett_equal_regular(x: U576, y: U576) -> TestResult {
+            let (x_e, y_e): (FieldElement<P521Field>, FieldElement<P521Field>) = (x.into(), y.into());
+            let (x_m, y_m): (MontgomeryElement<P521Field>, MontgomeryElement<P521Field>) = (x.into(), y.into());
+            let (x_b, y_b): (BarrettElement<P521Field>, BarrettElement<P521Field>) = (x.into(), y.into());
+
+            if y_e == FieldElement::zero() {
+                return TestResult::discard();
+            }
+
+            TestResult::from_bool(
+                (x_m * y_m).into_element() == x_e * y_e &&
+                    (x_m + y_m).into_element() == x_e + y_e &&
+                    (x_m / y_m).into_element() == x_e / y_e &&
+                    (x_b * y_b).into_element() == x_e * y_e &&
+                    (x_b - y_b).into_element() == x_e - y_e
+            )
+        }
+
+        fn montgomery_equals_regular_p384(x: U384, y: U384) -> bool {
+            let (x_e, y_e): (FieldElement<P384Field>, FieldElement<P384Field>) = (x.into(), y.into());
+            let (x_m, y_m): (MontgomeryElement<P384Field>, MontgomeryElement<P384Field>) = (x.into(), y.into());
+            let (x_b, y_b): (BarrettElement<P384Field>, BarrettElement<P384Field>) = (x.into(), y.into());
+            let (x_c, y_c): (ConstantTimeElement<P384Field>, ConstantTimeElement<P384Field>) = (x.into(), y.into());
+
+            (x_m * y_m).into_element() == x_e * y_e &&
+                (x_b * y_b).into_element() == x_e * y_e &&
+                (x_c * y_c + x_c).into_element() == x_e * y_e + x_e &&
+                (x_c - y_c).into_element() == x_e - y_e &&
+                (-x_m).into_element() == -x_e
+        }
+    }
+}
@@ -0,0 +1,68 @@
+//! hash_to_field against the RFC 9380 (appendix J) vectors of the P-256 and secp256k1 suites
+
+extern crate ng_field as field;
+extern crate ng_uint as uint;
+extern crate serde_json;
+
+use field::{Field, hash_to_field};
+use uint::U256;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct P256Field;
+
+impl Field for P256Field {
+    type Value = U256;
+
+    const MODULUS: U256 = U256::from_raw(
+        [
+            0xffffffffffffffff,
+            0x00000000ffffffff,
+            0x0000000000000000,
+            0xffffffff00000001,
+        ]
+    );
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Secp256k1Field;
+
+impl Field for Secp256k1Field {
+    type Value = U256;
+
+    const MODULUS: U256 = U256::from_raw(
+        [
+            0xFFFFFFFEFFFFFC2F,
+            0xFFFFFFFFFFFFFFFF,
+            0xFFFFFFFFFFFFFFFF,
+            0xFFFFFFFFFFFFFFFF,
+        ]
+    );
+}
+
+/// Checks the two field elements u of every vector of the suite
+fn check_vectors<F: Field>(suite: &str) {
+    let data: serde_json::Value = serde_json::from_str(suite).unwrap();
+    let dst = data["dst"].as_str().unwrap().as_bytes();
+    let vectors = data["vectors"].as_array().unwrap();
+    assert_eq!(vectors.len(), 5);
+
+    for vector in vectors {
+        let u = hash_to_field::<F>(vector["msg"].as_str().unwrap().as_bytes(), dst, 2);
+
+        for (element, expected) in u.into_iter().zip(vector["u"].as_array().unwrap()) {
+            let expected = expected.as_str().unwrap().trim_start_matches("0x");
+            let hex: String = element.to_bytes_be().iter().map(|b| format!("{:02x}", b)).collect();
+            assert_eq!(hex, expected);
+        }
+    }
+}
+
+#[test]
+fn p256() {
+    check_vectors::<P256Field>(include_str!("data/P256_XMD_SHA-256_SSWU_RO_.json"));
+}
+
+#[test]
+fn secp256k1() {
+    check_vectors::<Secp256k1Field>(include_str!("data/secp256k1_XMD_SHA-256_SSWU_RO_.json"));
+}